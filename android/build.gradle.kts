@@ -36,6 +36,11 @@ dependencies {
 
     implementation("androidx.core:core-ktx:1.9.0")
     implementation("androidx.appcompat:appcompat:1.6.0")
+    implementation("androidx.exifinterface:exifinterface:1.3.7")
+    implementation("androidx.heifwriter:heifwriter:1.0.0")
+    // AvifWriter is only in the alpha release, so it is not added to the apps using this plugin.
+    // Apps that need AVIF add it themselves, and its availability is checked at runtime.
+    compileOnly("androidx.heifwriter:heifwriter:1.1.0-alpha02")
     implementation("com.google.android.material:material:1.7.0")
     testImplementation("junit:junit:4.13.2")
     androidTestImplementation("androidx.test.ext:junit:1.1.5")
@@ -3,21 +3,40 @@ import android.provider.Settings.ACTION_MANAGE_ALL_FILES_ACCESS_PERMISSION
 import android.provider.Settings.ACTION_MANAGE_APP_ALL_FILES_ACCESS_PERMISSION
 import android.annotation.SuppressLint
 import android.app.Activity
+import android.app.PendingIntent
+import android.content.BroadcastReceiver
+import android.content.ComponentName
+import android.content.Context
 import android.content.Intent
+import android.content.IntentFilter
+import android.content.ContentResolver
+import android.database.ContentObserver
+import android.content.pm.PackageManager
 import android.graphics.Bitmap
 import android.graphics.BitmapFactory
 import android.graphics.ImageDecoder
 import android.net.Uri
 import android.os.Build
 import android.os.Environment
+import android.os.StatFs
+import android.os.storage.StorageManager
+import android.os.storage.StorageVolume
 import android.provider.DocumentsContract
+import android.security.keystore.KeyGenParameterSpec
+import android.security.keystore.KeyProperties
+import android.system.Os
 import android.provider.MediaStore
+import android.provider.OpenableColumns
 import androidx.activity.result.ActivityResult
 import androidx.activity.result.PickVisualMediaRequest
 import androidx.activity.result.contract.ActivityResultContracts.PickMultipleVisualMedia
 import androidx.activity.result.contract.ActivityResultContracts.PickVisualMedia
 import androidx.core.app.ShareCompat
+import androidx.core.content.ContextCompat
+import androidx.core.graphics.drawable.toBitmap
+import androidx.core.database.getStringOrNull
 import android.webkit.MimeTypeMap
+import android.webkit.WebView
 import android.media.MediaMetadataRetriever
 import android.media.MediaMetadataRetriever.OPTION_PREVIOUS_SYNC
 import app.tauri.Logger
@@ -26,15 +45,27 @@ import app.tauri.annotation.Command
 import app.tauri.annotation.InvokeArg
 import app.tauri.annotation.Permission
 import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Channel
 import app.tauri.plugin.Invoke
 import app.tauri.plugin.JSArray
 import app.tauri.plugin.JSObject
 import app.tauri.plugin.Plugin
 import kotlinx.coroutines.Dispatchers
 import kotlinx.coroutines.CoroutineScope
+import kotlinx.coroutines.async
+import kotlinx.coroutines.awaitAll
 import kotlinx.coroutines.launch
+import kotlinx.coroutines.sync.Semaphore
+import kotlinx.coroutines.sync.withPermit
 import kotlinx.coroutines.withContext
 import kotlin.math.min
+import java.io.File
+import java.security.KeyStore
+import java.security.SecureRandom
+import javax.crypto.Cipher
+import javax.crypto.KeyGenerator
+import javax.crypto.SecretKey
+import javax.crypto.spec.GCMParameterSpec
 import java.io.OutputStream
 import android.Manifest
 
@@ -49,6 +80,11 @@ class GetNameArgs {
     lateinit var uri: FileUri
 }
 
+@InvokeArg
+class ExistsArgs {
+    lateinit var uri: FileUri
+}
+
 @InvokeArg
 class GetThumbnailArgs {
     lateinit var src: FileUri
@@ -59,6 +95,43 @@ class GetThumbnailArgs {
     lateinit var format: String
 }
 
+@InvokeArg
+class GetThumbnailsArgs {
+    lateinit var srcs: Array<FileUri>
+    lateinit var destDir: String
+    var width: Int = -1
+    var height: Int = -1
+    var quality: Int = -1
+    lateinit var format: String
+    lateinit var channel: Channel
+}
+
+@InvokeArg
+class Size {
+    var width: Int = -1
+    var height: Int = -1
+}
+
+@InvokeArg
+enum class ImageFit {
+    Contain,
+    Cover,
+    Fill
+}
+
+@InvokeArg
+class TranscodeImageArgs {
+    lateinit var src: FileUri
+    lateinit var dest: FileUri
+    lateinit var format: String
+    var quality: Int = -1
+    var maxSize: Size? = null
+    var exactSize: Size? = null
+    var fit: ImageFit = ImageFit.Contain
+    var stripMetadata: Boolean = true
+    var respectExifOrientation: Boolean = true
+}
+
 @InvokeArg
 class ShowOpenFileDialogArgs {
     lateinit var mimeTypes: Array<String>
@@ -72,9 +145,31 @@ class ShowOpenContentDialogArgs {
     var multiple: Boolean = false
 }
 
+@InvokeArg
+class PickerOptions {
+    var maxItems: Int = 1
+    var mimeTypes: Array<String> = arrayOf()
+    var localOnly: Boolean = false
+    var initialLocation: FileUri? = null
+    var ordered: Boolean = false
+}
+
+@InvokeArg
+class ShowPickerDialogArgs {
+    lateinit var options: PickerOptions
+}
+
+@InvokeArg
+class ShowOpenVisualMediaDialogWithArgs {
+    lateinit var target: VisualMediaPickerType
+    var mimeType: String? = null
+    lateinit var options: PickerOptions
+}
+
 @InvokeArg
 class ShowOpenVisualMediaDialogArgs {
     lateinit var target: VisualMediaPickerType
+    var mimeType: String? = null
     var multiple: Boolean = false
 }
 
@@ -101,7 +196,8 @@ enum class PersistableUriPermissionMode {
 enum class VisualMediaPickerType {
     ImageOnly,
     VideoOnly,
-    ImageAndVideo
+    ImageAndVideo,
+    SingleMimeType
 }
 
 @InvokeArg
@@ -167,6 +263,12 @@ class TakePersistableUriPermissionArgs {
     lateinit var uri: FileUri
 }
 
+@InvokeArg
+class TakePersistableUriPermissionWithArgs {
+    lateinit var uri: FileUri
+    lateinit var mode: PersistableUriPermissionMode
+}
+
 @InvokeArg
 class CheckPersistedUriPermissionArgs {
     lateinit var uri: FileUri
@@ -189,11 +291,79 @@ class ShareFileArgs {
     lateinit var uri: FileUri
 }
 
+@InvokeArg
+class RegisterIncomingIntentListenerArgs {
+    lateinit var channel: Channel
+}
+
+@InvokeArg
+class ShareArgs {
+    var uris: Array<FileUri> = arrayOf()
+    var text: String? = null
+    var subject: String? = null
+    var title: String? = null
+    var mimeOverride: String? = null
+}
+
 @InvokeArg
 class ViewFileArgs {
     lateinit var uri: FileUri
 }
 
+@InvokeArg
+class ViewArgs {
+    lateinit var uri: FileUri
+    var alwaysShowChooser: Boolean = true
+    var chooserTitle: String? = null
+    var targetPackage: String? = null
+    var grantWrite: Boolean = false
+    var channel: Channel? = null
+}
+
+@InvokeArg
+class GetViewAppsArgs {
+    var uri: FileUri? = null
+    var mimeType: String? = null
+    var iconSize: Int = 0
+    var iconDestDir: String? = null
+}
+
+@InvokeArg
+class WatchArgs {
+    var id: Long = 0
+    lateinit var uri: FileUri
+    var recursive: Boolean = false
+    lateinit var channel: Channel
+}
+
+@InvokeArg
+class UnwatchArgs {
+    var id: Long = 0
+}
+
+@InvokeArg
+class RegisterStorageVolumeListenerArgs {
+    lateinit var channel: Channel
+}
+
+@InvokeArg
+class GetAvailableSpaceArgs {
+    lateinit var target: String
+    var path: String? = null
+    var uri: FileUri? = null
+}
+
+@InvokeArg
+class ReserveSpaceArgs {
+    lateinit var uri: FileUri
+    var bytes: Long = 0
+}
+
+@InvokeArg
+class GetEncryptionKeyArgs {
+    lateinit var path: String
+}
+
 @InvokeArg
 class AppManageExternalUrlArgs {
     lateinit var appName: String
@@ -205,10 +375,35 @@ class AppManageExternalUrlArgs {
     ]
 )
 class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
+    companion object {
+        private const val THUMBNAILS_PARALLELISM = 4
+
+        // MediaStore.EXTRA_PICK_IMAGES_IN_ORDER (API level 35)
+        private const val EXTRA_PICK_IMAGES_IN_ORDER = "android.provider.extra.PICK_IMAGES_IN_ORDER"
+
+        // Activity の再生成時に同じ Intent を二重に処理しないための印
+        private const val EXTRA_INCOMING_INTENT_HANDLED = "pluginAndroidFs-incomingIntentHandled"
+
+        // 選択されたアプリを受け取る BroadcastReceiver の action の接頭辞
+        private const val ACTION_CHOSEN_COMPONENT_PREFIX = "pluginAndroidFs-chosenComponent-"
+
+        // 暗号化キーをラップする Android Keystore のキーの alias
+        private const val ENCRYPTION_KEY_WRAPPER_ALIAS = "pluginAndroidFs-encryptionKeyWrapper"
+    }
+
     private val isVisualMediaPickerAvailable = PickVisualMedia.isPhotoPickerAvailable()
     private val documentFileController = DocumentFileController(activity)
     private val mediaFileController = MediaFileController(activity)
     private val rawFileController = RawFileController()
+    private val imageEncoder = ImageEncoder(activity)
+    private val imageTranscoder = ImageTranscoder(activity, imageEncoder)
+
+    // Rust 側のリスナーが登録されるまで受信した Intent を保持する
+    private var incomingIntentChannel: Channel? = null
+    private val pendingIncomingIntents = mutableListOf<JSObject>()
+
+    // Rust 側の watch の ID ごとの ContentObserver
+    private val contentObservers = mutableMapOf<Long, ContentObserver>()
 
     @Suppress("NAME_SHADOWING")
     private fun getFileController(uri: FileUri): FileController {
@@ -264,6 +459,270 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         return null
     }
 
+    override fun load(webView: WebView) {
+        super.load(webView)
+        handleIncomingIntent(activity.intent)
+    }
+
+    override fun onNewIntent(intent: Intent) {
+        super.onNewIntent(intent)
+        handleIncomingIntent(intent)
+    }
+
+    @Command
+    fun registerIncomingIntentListener(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(RegisterIncomingIntentListenerArgs::class.java)
+
+            synchronized(pendingIncomingIntents) {
+                incomingIntentChannel = args.channel
+                pendingIncomingIntents.forEach { args.channel.send(it) }
+                pendingIncomingIntents.clear()
+            }
+
+            invoke.resolve()
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke registerIncomingIntentListener."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun watch(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(WatchArgs::class.java)
+            val uri = Uri.parse(args.uri.uri)
+
+            // DocumentsProvider はディレクトリの変更を子要素一覧の URI に通知するため、それも監視する
+            val childrenUri = if (DocumentsContract.isDocumentUri(activity, uri) && DocumentsContract.isTreeUri(uri)) {
+                DocumentsContract.buildChildDocumentsUriUsingTree(uri, DocumentsContract.getDocumentId(uri))
+            }
+            else {
+                null
+            }
+
+            val observer = object : ContentObserver(null) {
+                override fun onChange(selfChange: Boolean, uris: Collection<Uri>, flags: Int) {
+                    val kind = when {
+                        flags and ContentResolver.NOTIFY_INSERT != 0 -> "Create"
+                        flags and ContentResolver.NOTIFY_DELETE != 0 -> "Remove"
+                        else -> "Modify"
+                    }
+
+                    for (changed in uris) {
+                        val fileUri = if (changed == uri || changed == childrenUri) {
+                            args.uri
+                        }
+                        else {
+                            FileUri().apply {
+                                this.uri = changed.toString()
+                                this.documentTopTreeUri = if (DocumentsContract.isTreeUri(changed)) args.uri.documentTopTreeUri else null
+                            }
+                        }
+
+                        args.channel.send(JSObject().apply {
+                            put("uri", JSObject().apply {
+                                put("uri", fileUri.uri)
+                                put("documentTopTreeUri", fileUri.documentTopTreeUri)
+                            })
+                            put("kind", kind)
+                        })
+                    }
+                }
+            }
+
+            activity.contentResolver.registerContentObserver(uri, args.recursive, observer)
+            if (childrenUri != null) {
+                activity.contentResolver.registerContentObserver(childrenUri, args.recursive, observer)
+            }
+
+            synchronized(contentObservers) {
+                contentObservers.put(args.id, observer)?.let {
+                    activity.contentResolver.unregisterContentObserver(it)
+                }
+            }
+
+            invoke.resolve()
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke watch."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun unwatch(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(UnwatchArgs::class.java)
+
+            synchronized(contentObservers) {
+                contentObservers.remove(args.id)?.let {
+                    activity.contentResolver.unregisterContentObserver(it)
+                }
+            }
+
+            invoke.resolve()
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke unwatch."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun listStorageVolumes(invoke: Invoke) {
+        try {
+            val storageManager = activity.getSystemService(StorageManager::class.java)
+            val volumes = JSArray()
+            for (volume in storageManager.storageVolumes) {
+                volumes.put(storageVolumeToJson(volume))
+            }
+
+            invoke.resolve(JSObject().apply {
+                put("volumes", volumes)
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke listStorageVolumes."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun registerStorageVolumeListener(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(RegisterStorageVolumeListenerArgs::class.java)
+            val storageManager = activity.getSystemService(StorageManager::class.java)
+
+            storageManager.registerStorageVolumeCallback(
+                activity.mainExecutor,
+                object : StorageManager.StorageVolumeCallback() {
+                    override fun onStateChanged(volume: StorageVolume) {
+                        try {
+                            args.channel.send(storageVolumeToJson(volume))
+                        }
+                        catch (ex: Exception) {
+                            Logger.error(ex.message ?: "Failed to send storage volume.")
+                        }
+                    }
+                }
+            )
+
+            invoke.resolve()
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke registerStorageVolumeListener."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    private fun storageVolumeToJson(volume: StorageVolume): JSObject {
+        val state = when (volume.state) {
+            Environment.MEDIA_MOUNTED -> "Mounted"
+            Environment.MEDIA_MOUNTED_READ_ONLY -> "MountedReadOnly"
+            Environment.MEDIA_UNMOUNTED -> "Unmounted"
+            Environment.MEDIA_CHECKING -> "Checking"
+            Environment.MEDIA_EJECTING -> "Ejecting"
+            Environment.MEDIA_REMOVED -> "Removed"
+            Environment.MEDIA_BAD_REMOVAL -> "BadRemoval"
+            Environment.MEDIA_UNMOUNTABLE -> "Unmountable"
+            else -> "Unknown"
+        }
+
+        // マウントされていない場合は directory が null になる
+        val statFs = try {
+            volume.directory?.let { StatFs(it.path) }
+        }
+        catch (ignore: Exception) {
+            null
+        }
+
+        return JSObject().apply {
+            // ExternalStorageProvider の document ID の接頭辞と同じ
+            put("id", if (volume.isPrimary) "primary" else (volume.uuid ?: ""))
+            put("description", volume.getDescription(activity))
+            put("removable", volume.isRemovable)
+            put("primary", volume.isPrimary)
+            put("state", state)
+            put("totalBytes", statFs?.totalBytes)
+            put("freeBytes", statFs?.availableBytes)
+        }
+    }
+
+    private fun handleIncomingIntent(intent: Intent?) {
+        try {
+            if (intent == null || intent.getBooleanExtra(EXTRA_INCOMING_INTENT_HANDLED, false)) {
+                return
+            }
+
+            val obj = parseIncomingIntent(intent) ?: return
+            intent.putExtra(EXTRA_INCOMING_INTENT_HANDLED, true)
+
+            synchronized(pendingIncomingIntents) {
+                val channel = incomingIntentChannel
+                if (channel != null) {
+                    channel.send(obj)
+                }
+                else {
+                    pendingIncomingIntents.add(obj)
+                }
+            }
+        }
+        catch (ex: Exception) {
+            Logger.error(ex.message ?: "Failed to handle incoming intent.")
+        }
+    }
+
+    @Suppress("DEPRECATION")
+    private fun parseIncomingIntent(intent: Intent): JSObject? {
+        val uris = mutableListOf<Uri>()
+        val action = when (intent.action) {
+            Intent.ACTION_SEND -> {
+                intent.getParcelableExtra<Uri>(Intent.EXTRA_STREAM)?.let { uris.add(it) }
+                "Send"
+            }
+            Intent.ACTION_SEND_MULTIPLE -> {
+                intent.getParcelableArrayListExtra<Uri>(Intent.EXTRA_STREAM)?.let { uris.addAll(it) }
+                "SendMultiple"
+            }
+            Intent.ACTION_VIEW -> {
+                // http などのディープリンクは対象外
+                intent.data?.takeIf { it.scheme == "content" || it.scheme == "file" }?.let { uris.add(it) }
+                "View"
+            }
+            else -> return null
+        }
+
+        val text = intent.getCharSequenceExtra(Intent.EXTRA_TEXT)?.toString()
+        val subject = intent.getStringExtra(Intent.EXTRA_SUBJECT)
+
+        if (uris.isEmpty() && text == null) {
+            return null
+        }
+
+        val jsUris = JSArray()
+        for (uri in uris) {
+            jsUris.put(JSObject().apply {
+                put("uri", uri.toString())
+                put("documentTopTreeUri", null)
+            })
+        }
+
+        return JSObject().apply {
+            put("action", action)
+            put("uris", jsUris)
+            put("mimeType", intent.type)
+            put("text", text)
+            put("subject", subject)
+        }
+    }
+
     @Command
     fun getAllPersistedUriPermissions(invoke: Invoke) {
         try {
@@ -286,6 +745,7 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
                             put("r", it.isReadPermission)
                             put("w", it.isWritePermission)
                             put("d", true)
+                            put("t", it.persistedTime)
                         }
                     }
                     else -> {
@@ -297,6 +757,7 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
                             put("r", it.isReadPermission)
                             put("w", it.isWritePermission)
                             put("d", false)
+                            put("t", it.persistedTime)
                         }
                     }
                 };
@@ -316,6 +777,25 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @Command
+    fun getPersistedUriPermissionLimit(invoke: Invoke) {
+        try {
+            val value = when {
+                Build.VERSION.SDK_INT >= Build.VERSION_CODES.R -> 512
+                else -> 128
+            }
+
+            invoke.resolve(JSObject().apply {
+                put("value", value)
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke getPersistedUriPermissionLimit."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
     @Command
     fun releaseAllPersistedUriPermissions(invoke: Invoke) {
         try {
@@ -403,6 +883,47 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @Command
+    fun takePersistableUriPermissionWith(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(TakePersistableUriPermissionWithArgs::class.java)
+
+            val uri = if (args.uri.documentTopTreeUri != null) {
+                Uri.parse(args.uri.documentTopTreeUri)
+            }
+            else {
+                Uri.parse(args.uri.uri)
+            }
+
+            val flags = when (args.mode) {
+                PersistableUriPermissionMode.Read -> Intent.FLAG_GRANT_READ_URI_PERMISSION
+                PersistableUriPermissionMode.Write -> Intent.FLAG_GRANT_WRITE_URI_PERMISSION
+                PersistableUriPermissionMode.ReadAndWrite -> Intent.FLAG_GRANT_READ_URI_PERMISSION or Intent.FLAG_GRANT_WRITE_URI_PERMISSION
+            }
+
+            // ピッカーの結果で付与されたフラグに要求されたモードが含まれているか確認する
+            var granted = activity.checkCallingOrSelfUriPermission(uri, flags) == PackageManager.PERMISSION_GRANTED
+            if (granted) {
+                try {
+                    activity.contentResolver.takePersistableUriPermission(uri, flags)
+                }
+                catch (ignore: SecurityException) {
+                    // FLAG_GRANT_PERSISTABLE_URI_PERMISSION が付与されていない
+                    granted = false
+                }
+            }
+
+            invoke.resolve(JSObject().apply {
+                put("value", granted)
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke takePersistableUriPermissionWith."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
     @Command
     fun checkPersistedUriPermission(invoke: Invoke) {
         try {
@@ -590,19 +1111,54 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
     }
 
     @Command
-    fun getThumbnail(invoke: Invoke) {
+    fun exists(invoke: Invoke) {
         try {
-            CoroutineScope(Dispatchers.IO).launch {
-                try {
-                    val res = JSObject()
-                    res.put("value", _getThumbnail(invoke))
-                    
-                    withContext(Dispatchers.Main) {
-                        invoke.resolve(res) 
+            val args = invoke.parseArgs(ExistsArgs::class.java)
+            val uri = Uri.parse(args.uri.uri)
+
+            val value = when (uri.scheme) {
+                "file" -> File(uri.path ?: "").exists()
+                else -> {
+                    try {
+                        activity.contentResolver
+                            .query(uri, arrayOf(OpenableColumns.DISPLAY_NAME), null, null, null)
+                            ?.use { it.moveToFirst() } ?: false
+                    }
+                    catch (ex: SecurityException) {
+                        throw ex
+                    }
+                    catch (ignore: Exception) {
+                        // プロバイダによっては存在しない場合に例外を投げる
+                        false
                     }
                 }
-                catch (ex: Exception) {
-                    val message = ex.message ?: "Failed to invoke getThumbnail."
+            }
+
+            invoke.resolve(JSObject().apply {
+                put("value", value)
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke exists."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun getThumbnail(invoke: Invoke) {
+        try {
+            CoroutineScope(Dispatchers.IO).launch {
+                try {
+                    val res = JSObject()
+                    res.put("value", _getThumbnail(invoke))
+                    
+                    withContext(Dispatchers.Main) {
+                        invoke.resolve(res) 
+                    }
+                }
+                catch (ex: Exception) {
+                    val message = ex.message ?: "Failed to invoke getThumbnail."
                     Logger.error(message)
                     invoke.reject(message)
                 }
@@ -616,32 +1172,45 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
     }
     
     private fun _getThumbnail(invoke: Invoke): Boolean {
-        var out: OutputStream? = null
+        val args = invoke.parseArgs(GetThumbnailArgs::class.java)
+
+        return generateThumbnail(
+            args.src,
+            Uri.parse(args.dest.uri),
+            args.width,
+            args.height,
+            args.format,
+            args.quality
+        )
+    }
+
+    private fun generateThumbnail(
+        src: FileUri,
+        dest: Uri,
+        width: Int,
+        height: Int,
+        format: String,
+        quality: Int
+    ): Boolean {
+
         var thumbnail: Bitmap? = null
         var img: Bitmap? = null
 
         try {
-            val args = invoke.parseArgs(GetThumbnailArgs::class.java)
-            val dest = Uri.parse(args.dest.uri)
-            val width = args.width
-            val height = args.height
-            val compressFormat = when (args.format.lowercase()) {
-                "jpeg" -> Bitmap.CompressFormat.JPEG
-                "png" -> Bitmap.CompressFormat.PNG
-                "webp" -> Bitmap.CompressFormat.WEBP_LOSSY
-                else -> throw Exception("Illegal format: $args.format")
-            }
-
-            img = getFileController(args.src).getThumbnail(
-                args.src,
+            if (!imageEncoder.isAvailable(format)) {
+                throw Exception("Unavailable format: $format")
+            }
+
+            img = getFileController(src).getThumbnail(
+                src,
                 width,
                 height
             )
 
-            val srcUri = Uri.parse(args.src.uri)
+            val srcUri = Uri.parse(src.uri)
             if (img == null && srcUri.scheme == "content") {
                 try {
-                    val mimeType = getFileController(args.src).getMimeType(args.src)
+                    val mimeType = getFileController(src).getMimeType(src)
                     if (mimeType != null && mimeType.startsWith("video/")) {
                         img = getVideoThumbnail(
                             srcUri, 
@@ -670,25 +1239,138 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
                 img
             }
             
-            out = activity.contentResolver
-                .openOutputStream(dest, "wt")
-                ?: throw Exception("Failed to open output stream: $dest")
-
-            if (!thumbnail.compress(compressFormat, args.quality.coerceIn(0, 100), out)) {
-                throw Exception("Bitmap.compress() returned false for $dest")
-            }
-            
-            out.flush()
+            imageEncoder.encode(thumbnail, format, quality, dest)
             
             return true
         }
         finally {
-            out?.close()
             thumbnail?.recycle()
             img?.recycle()
         }
     }
 
+    @Command
+    fun getThumbnails(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(GetThumbnailsArgs::class.java)
+
+            CoroutineScope(Dispatchers.IO).launch {
+                try {
+                    val semaphore = Semaphore(THUMBNAILS_PARALLELISM)
+                    val destDir = File(args.destDir)
+
+                    args.srcs.mapIndexed { index, src ->
+                        async {
+                            semaphore.withPermit {
+                                val res = JSObject()
+                                res.put("index", index)
+
+                                try {
+                                    val dest = Uri.fromFile(File(destDir, index.toString()))
+                                    res.put("value", generateThumbnail(
+                                        src,
+                                        dest,
+                                        args.width,
+                                        args.height,
+                                        args.format,
+                                        args.quality
+                                    ))
+                                }
+                                catch (ex: Exception) {
+                                    res.put("error", ex.message ?: "Failed to get thumbnail.")
+                                }
+
+                                args.channel.send(res)
+                            }
+                        }
+                    }.awaitAll()
+
+                    withContext(Dispatchers.Main) {
+                        invoke.resolve()
+                    }
+                }
+                catch (ex: Exception) {
+                    withContext(Dispatchers.Main) {
+                        val message = ex.message ?: "Failed to invoke getThumbnails."
+                        Logger.error(message)
+                        invoke.reject(message)
+                    }
+                }
+            }
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke getThumbnails."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun getAvailableImageFormats(invoke: Invoke) {
+        try {
+            CoroutineScope(Dispatchers.IO).launch {
+                try {
+                    val res = JSObject()
+                    for (format in arrayOf("Png", "Jpeg", "Webp", "WebpLossless", "Heic", "Avif")) {
+                        res.put(format.replaceFirstChar { it.lowercase() }, imageEncoder.isAvailable(format))
+                    }
+
+                    withContext(Dispatchers.Main) {
+                        invoke.resolve(res)
+                    }
+                }
+                catch (ex: Exception) {
+                    withContext(Dispatchers.Main) {
+                        val message = ex.message ?: "Failed to invoke getAvailableImageFormats."
+                        Logger.error(message)
+                        invoke.reject(message)
+                    }
+                }
+            }
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke getAvailableImageFormats."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun transcodeImage(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(TranscodeImageArgs::class.java)
+
+            CoroutineScope(Dispatchers.IO).launch {
+                try {
+                    val srcMimeType = try {
+                        getFileController(args.src).getMimeType(args.src)
+                    }
+                    catch (ignore: Exception) {
+                        null
+                    }
+
+                    imageTranscoder.transcode(args, srcMimeType)
+
+                    withContext(Dispatchers.Main) {
+                        invoke.resolve()
+                    }
+                }
+                catch (ex: Exception) {
+                    withContext(Dispatchers.Main) {
+                        val message = ex.message ?: "Failed to invoke transcodeImage."
+                        Logger.error(message)
+                        invoke.reject(message)
+                    }
+                }
+            }
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke transcodeImage."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
     private fun getVideoThumbnail(uri: Uri, width: Int, height: Int): Bitmap? {
         MediaMetadataRetriever().use { mediaMetadataRetriever ->
             mediaMetadataRetriever.setDataSource(activity, uri)
@@ -802,6 +1484,182 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @Command
+    fun getAvailableSpace(invoke: Invoke) {
+        CoroutineScope(Dispatchers.IO).launch {
+            try {
+                val args = invoke.parseArgs(GetAvailableSpaceArgs::class.java)
+                val value = when (args.target) {
+                    "path" -> getAllocatableBytes(File(args.path!!))
+                    "public" -> {
+                        @Suppress("DEPRECATION")
+                        getAllocatableBytes(Environment.getExternalStorageDirectory())
+                    }
+                    else -> {
+                        val uri = Uri.parse(args.uri!!.uri)
+                        if (uri.scheme == "file") {
+                            getAllocatableBytes(File(uri.path!!))
+                        }
+                        else {
+                            // content URI は実際のパスが分からないため、ファイルが存在するファイルシステムの空き容量を返す
+                            activity.contentResolver.openFileDescriptor(uri, "r")!!.use {
+                                val stat = Os.fstatvfs(it.fileDescriptor)
+                                stat.f_bavail * stat.f_frsize
+                            }
+                        }
+                    }
+                }
+
+                withContext(Dispatchers.Main) {
+                    invoke.resolve(JSObject().apply {
+                        put("value", value)
+                    })
+                }
+            }
+            catch (ex: Exception) {
+                val message = ex.message ?: "Failed to invoke getAvailableSpace."
+                Logger.error(message)
+                withContext(Dispatchers.Main) {
+                    invoke.reject(message)
+                }
+            }
+        }
+    }
+
+    @Command
+    fun reserveSpace(invoke: Invoke) {
+        CoroutineScope(Dispatchers.IO).launch {
+            try {
+                val args = invoke.parseArgs(ReserveSpaceArgs::class.java)
+                val storageManager = activity.getSystemService(StorageManager::class.java)
+
+                // "w" は切り詰められる可能性があるため "rw" で開く
+                activity.contentResolver.openFileDescriptor(Uri.parse(args.uri.uri), "rw")!!.use {
+                    storageManager.allocateBytes(it.fileDescriptor, args.bytes)
+                }
+
+                withContext(Dispatchers.Main) {
+                    invoke.resolve()
+                }
+            }
+            catch (ex: Exception) {
+                val message = ex.message ?: "Failed to invoke reserveSpace."
+                Logger.error(message)
+                withContext(Dispatchers.Main) {
+                    invoke.reject(message)
+                }
+            }
+        }
+    }
+
+    @Command
+    fun getCacheQuotaBytes(invoke: Invoke) {
+        try {
+            val storageManager = activity.getSystemService(StorageManager::class.java)
+            val uuid = storageManager.getUuidForPath(activity.cacheDir)
+
+            invoke.resolve(JSObject().apply {
+                put("value", storageManager.getCacheQuotaBytes(uuid))
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke getCacheQuotaBytes."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun getEncryptionKey(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(GetEncryptionKeyArgs::class.java)
+            val file = File(args.path)
+            val wrapper = getOrCreateEncryptionKeyWrapper()
+
+            // ファイルの形式は [IV の長さ (1 byte)][IV][ラップされたキー]
+            val key = if (file.exists()) {
+                val bytes = file.readBytes()
+                val ivLen = bytes[0].toInt() and 0xff
+                val iv = bytes.copyOfRange(1, 1 + ivLen)
+                val wrapped = bytes.copyOfRange(1 + ivLen, bytes.size)
+
+                val cipher = Cipher.getInstance("AES/GCM/NoPadding")
+                cipher.init(Cipher.DECRYPT_MODE, wrapper, GCMParameterSpec(128, iv))
+                cipher.doFinal(wrapped)
+            }
+            else {
+                val key = ByteArray(32)
+                SecureRandom().nextBytes(key)
+
+                val cipher = Cipher.getInstance("AES/GCM/NoPadding")
+                cipher.init(Cipher.ENCRYPT_MODE, wrapper)
+                val iv = cipher.iv
+                val wrapped = cipher.doFinal(key)
+
+                // 途中で終了しても壊れたファイルが残らないように、一時ファイルに書き込んでから置き換える
+                file.parentFile?.mkdirs()
+                val tmp = File(file.path + ".tmp")
+                tmp.outputStream().use {
+                    it.write(byteArrayOf(iv.size.toByte()))
+                    it.write(iv)
+                    it.write(wrapped)
+                    it.fd.sync()
+                }
+                if (!tmp.renameTo(file)) {
+                    throw Exception("Failed to save the encryption key.")
+                }
+                key
+            }
+
+            val res = JSArray()
+            for (b in key) {
+                res.put(b.toInt() and 0xff)
+            }
+
+            invoke.resolve(JSObject().apply {
+                put("key", res)
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke getEncryptionKey."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    private fun getOrCreateEncryptionKeyWrapper(): SecretKey {
+        val keyStore = KeyStore.getInstance("AndroidKeyStore").apply { load(null) }
+
+        (keyStore.getKey(ENCRYPTION_KEY_WRAPPER_ALIAS, null) as? SecretKey)?.let {
+            return it
+        }
+
+        val generator = KeyGenerator.getInstance(KeyProperties.KEY_ALGORITHM_AES, "AndroidKeyStore")
+        generator.init(
+            KeyGenParameterSpec.Builder(
+                ENCRYPTION_KEY_WRAPPER_ALIAS,
+                KeyProperties.PURPOSE_ENCRYPT or KeyProperties.PURPOSE_DECRYPT
+            )
+                .setBlockModes(KeyProperties.BLOCK_MODE_GCM)
+                .setEncryptionPaddings(KeyProperties.ENCRYPTION_PADDING_NONE)
+                .setKeySize(256)
+                .build()
+        )
+        return generator.generateKey()
+    }
+
+    private fun getAllocatableBytes(file: File): Long {
+        // 書き込み前でまだ存在しないパスの場合は、存在する祖先を使う
+        var target: File? = file
+        while (target != null && !target.exists()) {
+            target = target.parentFile
+        }
+
+        val storageManager = activity.getSystemService(StorageManager::class.java)
+        val uuid = storageManager.getUuidForPath(target ?: file)
+        return storageManager.getAllocatableBytes(uuid)
+    }
+
     @Command
     fun shareFile(invoke: Invoke) {
         try {
@@ -841,6 +1699,44 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @Command
+    fun share(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(ShareArgs::class.java)
+            val intent = createShareIntent(args)
+
+            activity.applicationContext.startActivity(intent)
+            invoke.resolve()
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke share."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun canShare(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(ShareArgs::class.java)
+            val value = try {
+                createShareIntent(args).resolveActivity(activity.packageManager) != null
+            }
+            catch (ignore: IllegalArgumentException) {
+                false
+            }
+
+            invoke.resolve(JSObject().apply {
+                put("value", value)
+            })
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke canShare."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
     @Command
     fun viewFile(invoke: Invoke) {
         try {
@@ -880,6 +1776,99 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @Command
+    fun view(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(ViewArgs::class.java)
+            val (baseIntent, intent) = createViewIntent(args)
+
+            activity.applicationContext.startActivity(intent)
+
+            // chooser を経由しない場合は、開かれるアプリが一意に決まるときのみ通知する
+            val channel = args.channel
+            if (channel != null && !args.alwaysShowChooser) {
+                val info = baseIntent.resolveActivity(activity.packageManager)
+                if (info != null && info.packageName != "android") {
+                    channel.send(JSObject().apply {
+                        put("packageName", info.packageName)
+                        put("activityName", info.className)
+                    })
+                }
+            }
+
+            invoke.resolve()
+        }
+        catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke view."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun getViewApps(invoke: Invoke) {
+        CoroutineScope(Dispatchers.IO).launch {
+            try {
+                val args = invoke.parseArgs(GetViewAppsArgs::class.java)
+                val intent = Intent(Intent.ACTION_VIEW)
+                val uri = args.uri?.let { Uri.parse(it.uri) }
+                
+                if (uri != null) {
+                    intent.setDataAndType(uri, args.mimeType ?: activity.contentResolver.getType(uri))
+                }
+                else {
+                    intent.setType(args.mimeType ?: "*/*")
+                }
+
+                val pm = activity.packageManager
+                val apps = JSArray()
+
+                pm.queryIntentActivities(intent, PackageManager.MATCH_DEFAULT_ONLY).forEachIndexed { i, info ->
+                    val activityInfo = info.activityInfo
+                    if (activityInfo.packageName == activity.packageName) {
+                        return@forEachIndexed
+                    }
+
+                    val icon = args.iconDestDir?.let { dir ->
+                        try {
+                            val bitmap = info.loadIcon(pm).toBitmap(args.iconSize, args.iconSize)
+                            val file = File(dir, "$i.png")
+                            file.outputStream().use {
+                                bitmap.compress(Bitmap.CompressFormat.PNG, 100, it)
+                            }
+                            file.absolutePath
+                        }
+                        catch (ignore: Exception) {
+                            null
+                        }
+                    }
+
+                    apps.put(JSObject().apply {
+                        put("component", JSObject().apply {
+                            put("packageName", activityInfo.packageName)
+                            put("activityName", activityInfo.name)
+                        })
+                        put("label", info.loadLabel(pm).toString())
+                        put("icon", icon)
+                    })
+                }
+
+                withContext(Dispatchers.Main) {
+                    invoke.resolve(JSObject().apply {
+                        put("apps", apps)
+                    })
+                }
+            }
+            catch (ex: Exception) {
+                val message = ex.message ?: "Failed to invoke getViewApps."
+                Logger.error(message)
+                withContext(Dispatchers.Main) {
+                    invoke.reject(message)
+                }
+            }
+        }
+    }
+
     @Command
     fun showManageDirDialog(invoke: Invoke) {
         try {
@@ -962,6 +1951,10 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
                 res.put("uri", null)
             }
 
+            val flags = result.data?.flags ?: 0
+            res.put("r", (flags and Intent.FLAG_GRANT_READ_URI_PERMISSION) != 0)
+            res.put("w", (flags and Intent.FLAG_GRANT_WRITE_URI_PERMISSION) != 0)
+
             invoke.resolve(res)
         } catch (ex: java.lang.Exception) {
             val message = ex.message ?: "Failed to invoke dirDialogResult."
@@ -1037,7 +2030,10 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
     fun showOpenVisualMediaDialog(invoke: Invoke) {
         try {
             val args = invoke.parseArgs(ShowOpenVisualMediaDialogArgs::class.java)
-            val intent = createVisualMediaPickerIntent(args.multiple, args.target)
+            val options = PickerOptions().apply {
+                maxItems = if (args.multiple) Int.MAX_VALUE else 1
+            }
+            val intent = createVisualMediaPickerIntent(args.target, args.mimeType, options)
 
             startActivityForResult(invoke, intent, "handleShowOpenFileAndVisualMediaDialog")
         } catch (ex: Exception) {
@@ -1047,6 +2043,63 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @Command
+    fun showOpenFileDialogWith(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(ShowPickerDialogArgs::class.java)
+            val options = args.options
+            val intent = createFilePickerIntent(options.mimeTypes, 1 < options.maxItems)
+
+            if (options.localOnly) {
+                intent.putExtra(Intent.EXTRA_LOCAL_ONLY, true)
+            }
+            options.initialLocation?.let { uri ->
+                tryAsDocumentUri(uri)?.let { dUri ->
+                    intent.putExtra(DocumentsContract.EXTRA_INITIAL_URI, dUri)
+                }
+            }
+
+            startActivityForResult(invoke, intent, "handleShowPickerDialog")
+        } catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke showOpenFileDialogWith."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun showOpenContentDialogWith(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(ShowPickerDialogArgs::class.java)
+            val options = args.options
+            val intent = createContentPickerIntent(options.mimeTypes, 1 < options.maxItems)
+
+            if (options.localOnly) {
+                intent.putExtra(Intent.EXTRA_LOCAL_ONLY, true)
+            }
+
+            startActivityForResult(invoke, intent, "handleShowPickerDialog")
+        } catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke showOpenContentDialogWith."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
+    @Command
+    fun showOpenVisualMediaDialogWith(invoke: Invoke) {
+        try {
+            val args = invoke.parseArgs(ShowOpenVisualMediaDialogWithArgs::class.java)
+            val intent = createVisualMediaPickerIntent(args.target, args.mimeType, args.options)
+
+            startActivityForResult(invoke, intent, "handleShowPickerDialog")
+        } catch (ex: Exception) {
+            val message = ex.message ?: "Failed to invoke showOpenVisualMediaDialogWith."
+            Logger.error(message)
+            invoke.reject(message)
+        }
+    }
+
     @Command
     fun showSaveFileDialog(invoke: Invoke) {
         try {
@@ -1199,6 +2252,99 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @ActivityCallback
+    fun handleShowPickerDialog(invoke: Invoke, result: ActivityResult) {
+        CoroutineScope(Dispatchers.IO).launch {
+            try {
+                val args = invoke.parseArgs(ShowPickerDialogArgs::class.java)
+                val items = JSArray()
+
+                if (result.resultCode == Activity.RESULT_OK) {
+                    getPickedUris(result.data)
+                        .take(args.options.maxItems.coerceAtLeast(1))
+                        .forEach { items.put(queryPickedFile(it)) }
+                }
+
+                withContext(Dispatchers.Main) {
+                    invoke.resolve(JSObject().apply {
+                        put("items", items)
+                    })
+                }
+            } catch (ex: Exception) {
+                val message = ex.message ?: "Failed to read file pick result"
+                Logger.error(message)
+                withContext(Dispatchers.Main) {
+                    invoke.reject(message)
+                }
+            }
+        }
+    }
+
+    private fun getPickedUris(data: Intent?): List<Uri> {
+        if (data == null) {
+            return listOf()
+        }
+
+        val clipData = data.clipData
+        if (clipData == null) {
+            return listOfNotNull(data.data)
+        }
+
+        return (0 until clipData.itemCount).mapNotNull { clipData.getItemAt(it).uri }
+    }
+
+    // 名前、MIME type、サイズ、最終更新日時を一度のクエリで取得する
+    private fun queryPickedFile(uri: Uri): JSObject {
+        var name: String? = null
+        var len: Long? = null
+        var lastModified: Long? = null
+
+        try {
+            activity.contentResolver.query(uri, null, null, null, null)?.use { c ->
+                if (c.moveToFirst()) {
+                    val nameIndex = c.getColumnIndex(OpenableColumns.DISPLAY_NAME)
+                    if (0 <= nameIndex) {
+                        name = c.getStringOrNull(nameIndex)
+                    }
+
+                    val sizeIndex = c.getColumnIndex(OpenableColumns.SIZE)
+                    if (0 <= sizeIndex && !c.isNull(sizeIndex)) {
+                        len = c.getLong(sizeIndex)
+                    }
+
+                    val docLastModifiedIndex = c.getColumnIndex(DocumentsContract.Document.COLUMN_LAST_MODIFIED)
+                    val mediaDateModifiedIndex = c.getColumnIndex(MediaStore.MediaColumns.DATE_MODIFIED)
+                    if (0 <= docLastModifiedIndex && !c.isNull(docLastModifiedIndex)) {
+                        lastModified = c.getLong(docLastModifiedIndex)
+                    }
+                    else if (0 <= mediaDateModifiedIndex && !c.isNull(mediaDateModifiedIndex)) {
+                        // MediaStore は秒単位
+                        lastModified = c.getLong(mediaDateModifiedIndex) * 1000
+                    }
+                }
+            }
+        }
+        catch (ignore: Exception) {}
+
+        val mimeType = try {
+            activity.contentResolver.getType(uri)
+        }
+        catch (ignore: Exception) {
+            null
+        }
+
+        return JSObject().apply {
+            put("uri", JSObject().apply {
+                put("uri", uri.toString())
+                put("documentTopTreeUri", null)
+            })
+            put("name", name ?: uri.lastPathSegment ?: "")
+            put("mimeType", mimeType)
+            put("len", len)
+            put("lastModified", lastModified)
+        }
+    }
+
     private fun createPickFilesResult(data: Intent?): JSObject {
         val callResult = JSObject()
         if (data == null) {
@@ -1269,23 +2415,58 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         }
     }
 
+    @SuppressLint("NewApi")
     private fun createVisualMediaPickerIntent(
-        multiple: Boolean,
-        target: VisualMediaPickerType
+        target: VisualMediaPickerType,
+        mimeType: String?,
+        options: PickerOptions
     ): Intent {
 
+        // フォトピッカーが利用できない場合はドキュメントピッカーにフォールバックする
+        if (!isVisualMediaPickerAvailable) {
+            val mimeTypes = when (target) {
+                VisualMediaPickerType.ImageOnly -> arrayOf("image/*")
+                VisualMediaPickerType.VideoOnly -> arrayOf("video/*")
+                VisualMediaPickerType.ImageAndVideo -> arrayOf("image/*", "video/*")
+                VisualMediaPickerType.SingleMimeType -> arrayOf(mimeType ?: "*/*")
+            }
+            val intent = createFilePickerIntent(mimeTypes, 1 < options.maxItems)
+
+            if (options.localOnly) {
+                intent.putExtra(Intent.EXTRA_LOCAL_ONLY, true)
+            }
+            options.initialLocation?.let { uri ->
+                tryAsDocumentUri(uri)?.let { dUri ->
+                    intent.putExtra(DocumentsContract.EXTRA_INITIAL_URI, dUri)
+                }
+            }
+            return intent
+        }
+
         val req = PickVisualMediaRequest(
             when (target) {
                 VisualMediaPickerType.ImageOnly -> PickVisualMedia.ImageOnly
                 VisualMediaPickerType.VideoOnly -> PickVisualMedia.VideoOnly
                 VisualMediaPickerType.ImageAndVideo -> PickVisualMedia.ImageAndVideo
+                VisualMediaPickerType.SingleMimeType -> PickVisualMedia.SingleMimeType(
+                    mimeType ?: throw Exception("MIME type is required for SingleMimeType")
+                )
             }
         )
 
-        return when (multiple) {
-            true -> PickMultipleVisualMedia().createIntent(activity, req)
-            false -> PickVisualMedia().createIntent(activity, req)
+        if (options.maxItems <= 1) {
+            return PickVisualMedia().createIntent(activity, req)
         }
+
+        // システムの上限を超えると例外が発生する
+        val maxItems = min(options.maxItems, MediaStore.getPickImagesMaxLimit())
+        val intent = PickMultipleVisualMedia(maxItems).createIntent(activity, req)
+
+        if (options.ordered) {
+            intent.putExtra(EXTRA_PICK_IMAGES_IN_ORDER, true)
+        }
+
+        return intent
     }
 
     private fun createViewFileIntent(
@@ -1308,6 +2489,75 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
         return intent
     }
 
+    /**
+     * 元の ACTION_VIEW の Intent と、実際に startActivity する Intent を返す
+     */
+    private fun createViewIntent(args: ViewArgs): Pair<Intent, Intent> {
+        val uri = Uri.parse(args.uri.uri)
+        var flags = Intent.FLAG_GRANT_READ_URI_PERMISSION
+        if (args.grantWrite) {
+            flags = flags or Intent.FLAG_GRANT_WRITE_URI_PERMISSION
+        }
+
+        val baseIntent = Intent(Intent.ACTION_VIEW)
+            .setDataAndType(uri, activity.contentResolver.getType(uri))
+            .addFlags(flags)
+
+        args.targetPackage?.let { baseIntent.setPackage(it) }
+
+        if (!args.alwaysShowChooser) {
+            val intent = Intent(baseIntent).addFlags(Intent.FLAG_ACTIVITY_NEW_TASK)
+            return Pair(baseIntent, intent)
+        }
+
+        val sender = args.channel?.let { createChosenComponentSender(it) }
+        val intent = Intent.createChooser(baseIntent, args.chooserTitle ?: "", sender)
+            .addFlags(flags)
+            .addFlags(Intent.FLAG_ACTIVITY_NEW_TASK)
+            .putExtra(Intent.EXTRA_EXCLUDE_COMPONENTS, arrayOf(activity.componentName))
+
+        return Pair(baseIntent, intent)
+    }
+
+    /**
+     * chooser でユーザーが選択したアプリを channel に送る IntentSender を作成する。
+     * キャンセルされた場合は通知されないため、受信されなかった BroadcastReceiver はプロセス終了まで残る。
+     */
+    private fun createChosenComponentSender(channel: Channel): android.content.IntentSender {
+        val context = activity.applicationContext
+        val action = ACTION_CHOSEN_COMPONENT_PREFIX + System.nanoTime()
+
+        val receiver = object : BroadcastReceiver() {
+            override fun onReceive(ctx: Context, intent: Intent) {
+                context.unregisterReceiver(this)
+
+                @Suppress("DEPRECATION")
+                val component = intent.getParcelableExtra<ComponentName>(Intent.EXTRA_CHOSEN_COMPONENT) ?: return
+                channel.send(JSObject().apply {
+                    put("packageName", component.packageName)
+                    put("activityName", component.className)
+                })
+            }
+        }
+
+        ContextCompat.registerReceiver(
+            context,
+            receiver,
+            IntentFilter(action),
+            ContextCompat.RECEIVER_NOT_EXPORTED
+        )
+
+        // システムが EXTRA_CHOSEN_COMPONENT を追加できるよう MUTABLE にする
+        val pendingIntent = PendingIntent.getBroadcast(
+            context,
+            0,
+            Intent(action).setPackage(context.packageName),
+            PendingIntent.FLAG_UPDATE_CURRENT or PendingIntent.FLAG_MUTABLE
+        )
+
+        return pendingIntent.intentSender
+    }
+
     private fun createShareFileIntent(
         uri: Uri,
         mimeType: String?
@@ -1328,4 +2578,52 @@ class AndroidFsPlugin(private val activity: Activity) : Plugin(activity) {
 
         return intent
     }
-}
\ No newline at end of file
+
+    private fun createShareIntent(args: ShareArgs): Intent {
+        val uris = args.uris.map { Uri.parse(it.uri) }
+        if (uris.isEmpty() && args.text == null) {
+            throw IllegalArgumentException("Nothing to share. Specify uris or text.")
+        }
+
+        val mimeType = args.mimeOverride
+            ?: getCommonMimeType(uris.map { activity.contentResolver.getType(it) })
+            ?: "text/plain"
+
+        // ストリームが複数の場合は ACTION_SEND_MULTIPLE になる
+        val builder = ShareCompat.IntentBuilder(activity).setType(mimeType)
+        uris.forEach { builder.addStream(it) }
+        args.text?.let { builder.setText(it) }
+        args.subject?.let { builder.setSubject(it) }
+        args.title?.let { builder.setChooserTitle(it) }
+
+        val intent = builder
+            .createChooserIntent()
+            .addFlags(Intent.FLAG_GRANT_READ_URI_PERMISSION)
+            .addFlags(Intent.FLAG_ACTIVITY_NEW_TASK)
+
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.N) {
+            intent.putExtra(Intent.EXTRA_EXCLUDE_COMPONENTS, arrayOf(activity.componentName))
+        }
+
+        return intent
+    }
+
+    // 例: [image/png, image/jpeg] -> image/*, [image/png, application/pdf] -> */*
+    private fun getCommonMimeType(mimeTypes: List<String?>): String? {
+        if (mimeTypes.isEmpty()) {
+            return null
+        }
+
+        val types = mimeTypes.map { it ?: "application/octet-stream" }.distinct()
+        if (types.size == 1) {
+            return types[0]
+        }
+
+        val topLevels = types.map { it.substringBefore('/') }.distinct()
+        if (topLevels.size == 1) {
+            return "${topLevels[0]}/*"
+        }
+
+        return "*/*"
+    }
+}
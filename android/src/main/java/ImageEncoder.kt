@@ -0,0 +1,115 @@
+package com.plugin.android_fs
+
+import android.app.Activity
+import android.graphics.Bitmap
+import android.media.MediaCodecList
+import android.media.MediaFormat
+import android.net.Uri
+import androidx.heifwriter.AvifWriter
+import androidx.heifwriter.HeifWriter
+import java.io.FileDescriptor
+
+class ImageEncoder(private val activity: Activity) {
+
+    private val isHeicAvailable: Boolean by lazy {
+        hasEncoder(MediaFormat.MIMETYPE_IMAGE_ANDROID_HEIC) || hasEncoder(MediaFormat.MIMETYPE_VIDEO_HEVC)
+    }
+
+    private val isAvifAvailable: Boolean by lazy {
+        isAvifWriterAvailable() && hasEncoder(MediaFormat.MIMETYPE_VIDEO_AV1)
+    }
+
+    fun isAvailable(format: String): Boolean {
+        return when (format.lowercase()) {
+            "png", "jpeg", "webp", "webplossless" -> true
+            "heic" -> isHeicAvailable
+            "avif" -> isAvifAvailable
+            else -> false
+        }
+    }
+
+    fun encode(bitmap: Bitmap, format: String, quality: Int, dest: Uri) {
+        val clampedQuality = quality.coerceIn(0, 100)
+
+        when (format.lowercase()) {
+            "heic" -> {
+                if (!isHeicAvailable) {
+                    throw Exception("HEIC encoder is not available on this device.")
+                }
+                activity.contentResolver.openFileDescriptor(dest, "rwt")?.use { pfd ->
+                    HeifWriter.Builder(pfd.fileDescriptor, bitmap.width, bitmap.height, HeifWriter.INPUT_MODE_BITMAP)
+                        .setQuality(clampedQuality)
+                        .build()
+                        .use { writer ->
+                            writer.start()
+                            writer.addBitmap(bitmap)
+                            writer.stop(ENCODE_TIMEOUT_MS)
+                        }
+                } ?: throw Exception("Failed to open file descriptor: $dest")
+            }
+            "avif" -> {
+                if (!isAvifAvailable) {
+                    throw Exception("AVIF encoder is not available on this device.")
+                }
+                activity.contentResolver.openFileDescriptor(dest, "rwt")?.use { pfd ->
+                    AvifEncoder.encode(pfd.fileDescriptor, bitmap, clampedQuality)
+                } ?: throw Exception("Failed to open file descriptor: $dest")
+            }
+            else -> {
+                val compressFormat = getCompressFormat(format)
+
+                activity.contentResolver.openOutputStream(dest, "wt")?.use { out ->
+                    if (!bitmap.compress(compressFormat, clampedQuality, out)) {
+                        throw Exception("Bitmap.compress() returned false for $dest")
+                    }
+                    out.flush()
+                } ?: throw Exception("Failed to open output stream: $dest")
+            }
+        }
+    }
+
+    private fun getCompressFormat(format: String): Bitmap.CompressFormat {
+        return when (format.lowercase()) {
+            "jpeg" -> Bitmap.CompressFormat.JPEG
+            "png" -> Bitmap.CompressFormat.PNG
+            "webp" -> Bitmap.CompressFormat.WEBP_LOSSY
+            "webplossless" -> Bitmap.CompressFormat.WEBP_LOSSLESS
+            else -> throw Exception("Illegal format: $format")
+        }
+    }
+
+    private fun hasEncoder(mimeType: String): Boolean {
+        return MediaCodecList(MediaCodecList.REGULAR_CODECS).codecInfos.any { info ->
+            info.isEncoder && info.supportedTypes.any { it.equals(mimeType, ignoreCase = true) }
+        }
+    }
+
+    // AvifWriter は heifwriter 1.1.0 以降にしかないため、アプリ側で追加されている場合のみ使用できる
+    private fun isAvifWriterAvailable(): Boolean {
+        return try {
+            Class.forName("androidx.heifwriter.AvifWriter")
+            true
+        }
+        catch (ex: ClassNotFoundException) {
+            false
+        }
+    }
+
+    // AvifWriter が存在しない場合にクラスの読み込みで失敗しないように分離している
+    private object AvifEncoder {
+        fun encode(fd: FileDescriptor, bitmap: Bitmap, quality: Int) {
+            AvifWriter.Builder(fd, bitmap.width, bitmap.height, AvifWriter.INPUT_MODE_BITMAP)
+                .setQuality(quality)
+                .build()
+                .use { writer ->
+                    writer.start()
+                    writer.addBitmap(bitmap)
+                    writer.stop(ENCODE_TIMEOUT_MS)
+                }
+        }
+    }
+
+    companion object {
+        private const val ENCODE_TIMEOUT_MS = 10_000L
+    }
+}
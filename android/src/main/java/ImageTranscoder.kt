@@ -0,0 +1,240 @@
+package com.plugin.android_fs
+
+import android.app.Activity
+import android.graphics.Bitmap
+import android.graphics.BitmapFactory
+import android.graphics.ImageDecoder
+import android.graphics.Matrix
+import android.media.MediaMetadataRetriever
+import android.net.Uri
+import androidx.exifinterface.media.ExifInterface
+import kotlin.math.max
+import kotlin.math.min
+import kotlin.math.roundToInt
+
+class ImageTranscoder(
+    private val activity: Activity,
+    private val imageEncoder: ImageEncoder
+) {
+
+    fun transcode(args: TranscodeImageArgs, srcMimeType: String?) {
+        val src = Uri.parse(args.src.uri)
+        val dest = Uri.parse(args.dest.uri)
+
+        var img: Bitmap? = null
+        var resized: Bitmap? = null
+
+        try {
+            val decoded = when {
+                srcMimeType != null && srcMimeType.startsWith("video/") -> decodeVideoFrame(src)
+                else -> decodeImage(src, args)
+            }
+            img = decoded
+
+            val result = resize(decoded, args)
+            resized = result
+
+            imageEncoder.encode(result, args.format, args.quality, dest)
+
+            if (!args.stripMetadata && args.format.lowercase() == "jpeg") {
+                try {
+                    copyExif(src, dest, result, args.respectExifOrientation)
+                }
+                catch (ignore: Exception) {}
+            }
+        }
+        finally {
+            if (resized !== img) {
+                resized?.recycle()
+            }
+            img?.recycle()
+        }
+    }
+
+    private fun decodeImage(src: Uri, args: TranscodeImageArgs): Bitmap {
+        if (args.respectExifOrientation) {
+            // ImageDecoder applies EXIF orientation
+            val source = ImageDecoder.createSource(activity.contentResolver, src)
+            return ImageDecoder.decodeBitmap(source) { decoder, info, _ ->
+                decoder.allocator = ImageDecoder.ALLOCATOR_SOFTWARE
+                decoder.setTargetSampleSize(calculateSampleSize(info.size.width, info.size.height, args))
+            }
+        }
+
+        // BitmapFactory does not apply EXIF orientation
+        val bounds = BitmapFactory.Options().apply { inJustDecodeBounds = true }
+        activity.contentResolver.openInputStream(src)?.use {
+            BitmapFactory.decodeStream(it, null, bounds)
+        }
+        if (bounds.outWidth <= 0 || bounds.outHeight <= 0) {
+            throw Exception("Failed to decode image: $src")
+        }
+
+        val options = BitmapFactory.Options().apply {
+            inSampleSize = calculateSampleSize(bounds.outWidth, bounds.outHeight, args)
+        }
+        return activity.contentResolver.openInputStream(src)?.use {
+            BitmapFactory.decodeStream(it, null, options)
+        } ?: throw Exception("Failed to decode image: $src")
+    }
+
+    private fun decodeVideoFrame(src: Uri): Bitmap {
+        MediaMetadataRetriever().use { retriever ->
+            retriever.setDataSource(activity, src)
+
+            val durationMs = retriever
+                .extractMetadata(MediaMetadataRetriever.METADATA_KEY_DURATION)
+                ?.toLongOrNull()
+
+            val frame = when (durationMs) {
+                null -> retriever.frameAtTime
+                else -> retriever.getFrameAtTime(durationMs * 1000 / 2, MediaMetadataRetriever.OPTION_CLOSEST_SYNC)
+            }
+
+            return frame ?: throw Exception("Failed to decode video frame: $src")
+        }
+    }
+
+    private fun calculateSampleSize(width: Int, height: Int, args: TranscodeImageArgs): Int {
+        val (tw, th) = calculateTargetSize(width, height, args)
+
+        // The orientation may not be applied yet, so compare the short side and the long side respectively.
+        val srcShort = min(width, height)
+        val srcLong = max(width, height)
+        val targetShort = min(tw, th)
+        val targetLong = max(tw, th)
+
+        var sampleSize = 1
+        while (srcShort / (sampleSize * 2) >= targetShort && srcLong / (sampleSize * 2) >= targetLong) {
+            sampleSize *= 2
+        }
+        return sampleSize
+    }
+
+    // Cover の場合はクロップ前のサイズを返す
+    private fun calculateTargetSize(width: Int, height: Int, args: TranscodeImageArgs): Pair<Int, Int> {
+        val exactSize = args.exactSize
+        val maxSize = args.maxSize
+
+        if (exactSize != null) {
+            val wr = exactSize.width.toFloat() / width
+            val hr = exactSize.height.toFloat() / height
+
+            return when (args.fit) {
+                ImageFit.Contain -> scaleSize(width, height, min(wr, hr))
+                ImageFit.Cover -> scaleSize(width, height, max(wr, hr))
+                ImageFit.Fill -> Pair(exactSize.width, exactSize.height)
+            }
+        }
+        if (maxSize != null) {
+            val ratio = min(1f, min(maxSize.width.toFloat() / width, maxSize.height.toFloat() / height))
+            return scaleSize(width, height, ratio)
+        }
+        return Pair(width, height)
+    }
+
+    private fun scaleSize(width: Int, height: Int, ratio: Float): Pair<Int, Int> {
+        return Pair(
+            (width * ratio).roundToInt().coerceAtLeast(1),
+            (height * ratio).roundToInt().coerceAtLeast(1)
+        )
+    }
+
+    private fun resize(img: Bitmap, args: TranscodeImageArgs): Bitmap {
+        val (tw, th) = calculateTargetSize(img.width, img.height, args)
+
+        val scaled = if (tw == img.width && th == img.height) {
+            img
+        }
+        else {
+            Bitmap.createScaledBitmap(img, tw, th, true)
+        }
+
+        val exactSize = args.exactSize
+        if (exactSize == null || args.fit != ImageFit.Cover) {
+            return scaled
+        }
+
+        val cw = min(exactSize.width, scaled.width)
+        val ch = min(exactSize.height, scaled.height)
+        if (cw == scaled.width && ch == scaled.height) {
+            return scaled
+        }
+
+        val cropped = Bitmap.createBitmap(
+            scaled,
+            (scaled.width - cw) / 2,
+            (scaled.height - ch) / 2,
+            cw,
+            ch,
+            Matrix(),
+            true
+        )
+        if (scaled !== img) {
+            scaled.recycle()
+        }
+        return cropped
+    }
+
+    private fun copyExif(src: Uri, dest: Uri, result: Bitmap, orientationApplied: Boolean) {
+        val srcExif = activity.contentResolver.openInputStream(src)?.use {
+            ExifInterface(it)
+        } ?: return
+
+        activity.contentResolver.openFileDescriptor(dest, "rw")?.use { pfd ->
+            val destExif = ExifInterface(pfd.fileDescriptor)
+
+            for (tag in EXIF_TAGS) {
+                srcExif.getAttribute(tag)?.let { destExif.setAttribute(tag, it) }
+            }
+
+            destExif.setAttribute(ExifInterface.TAG_IMAGE_WIDTH, result.width.toString())
+            destExif.setAttribute(ExifInterface.TAG_IMAGE_LENGTH, result.height.toString())
+            destExif.setAttribute(ExifInterface.TAG_PIXEL_X_DIMENSION, result.width.toString())
+            destExif.setAttribute(ExifInterface.TAG_PIXEL_Y_DIMENSION, result.height.toString())
+
+            if (orientationApplied) {
+                destExif.setAttribute(ExifInterface.TAG_ORIENTATION, ExifInterface.ORIENTATION_NORMAL.toString())
+            }
+            else {
+                srcExif.getAttribute(ExifInterface.TAG_ORIENTATION)?.let {
+                    destExif.setAttribute(ExifInterface.TAG_ORIENTATION, it)
+                }
+            }
+
+            destExif.saveAttributes()
+        }
+    }
+
+    companion object {
+        private val EXIF_TAGS = arrayOf(
+            ExifInterface.TAG_DATETIME,
+            ExifInterface.TAG_DATETIME_ORIGINAL,
+            ExifInterface.TAG_DATETIME_DIGITIZED,
+            ExifInterface.TAG_OFFSET_TIME,
+            ExifInterface.TAG_OFFSET_TIME_ORIGINAL,
+            ExifInterface.TAG_OFFSET_TIME_DIGITIZED,
+            ExifInterface.TAG_MAKE,
+            ExifInterface.TAG_MODEL,
+            ExifInterface.TAG_SOFTWARE,
+            ExifInterface.TAG_ARTIST,
+            ExifInterface.TAG_COPYRIGHT,
+            ExifInterface.TAG_IMAGE_DESCRIPTION,
+            ExifInterface.TAG_EXPOSURE_TIME,
+            ExifInterface.TAG_F_NUMBER,
+            ExifInterface.TAG_ISO_SPEED_RATINGS,
+            ExifInterface.TAG_FOCAL_LENGTH,
+            ExifInterface.TAG_FOCAL_LENGTH_IN_35MM_FILM,
+            ExifInterface.TAG_FLASH,
+            ExifInterface.TAG_WHITE_BALANCE,
+            ExifInterface.TAG_GPS_LATITUDE,
+            ExifInterface.TAG_GPS_LATITUDE_REF,
+            ExifInterface.TAG_GPS_LONGITUDE,
+            ExifInterface.TAG_GPS_LONGITUDE_REF,
+            ExifInterface.TAG_GPS_ALTITUDE,
+            ExifInterface.TAG_GPS_ALTITUDE_REF,
+            ExifInterface.TAG_GPS_TIMESTAMP,
+            ExifInterface.TAG_GPS_DATESTAMP,
+        )
+    }
+}
@@ -2,6 +2,10 @@ use std::io::{Read as _, Write as _};
 use crate::*;
 
 
+/// Event emitted with [`StorageVolume`] as payload when the state of a storage volume is changed,  
+/// such as SD card being mounted or unmounted.
+pub const STORAGE_VOLUME_CHANGED_EVENT: &str = "android-fs://storage-volume-changed";
+
 /// ***Root API***  
 /// 
 /// # Examples
@@ -22,10 +26,41 @@ pub struct AndroidFs<R: tauri::Runtime> {
     #[cfg(target_os = "android")]
     pub(crate) intent_lock: std::sync::Mutex<()>,
 
+    #[cfg(target_os = "android")]
+    pub(crate) thumbnail_cache_state: std::sync::Mutex<crate::api::ThumbnailCacheState>,
+
+    #[cfg(target_os = "android")]
+    pub(crate) permission_registry_state: std::sync::Mutex<crate::api::PermissionRegistryState>,
+
+    #[cfg(target_os = "android")]
+    pub(crate) incoming_intents_state: std::sync::Mutex<crate::api::IncomingIntentsState>,
+
+    #[cfg(target_os = "android")]
+    pub(crate) cache_manager_state: std::sync::Mutex<crate::api::CacheManagerState>,
+
+    #[cfg(target_os = "android")]
+    pub(crate) store_state: std::sync::Mutex<crate::api::StoreState>,
+
+    #[cfg(target_os = "android")]
+    pub(crate) storage_volume_callbacks: std::sync::Mutex<Vec<StorageVolumeCallback>>,
+
+    #[cfg(target_os = "android")]
+    pub(crate) webview_protocol_exposed: std::sync::Mutex<std::collections::HashMap<String, FileUri>>,
+
+    #[cfg(all(target_os = "android", feature = "watch"))]
+    pub(crate) watcher_state: std::sync::Mutex<crate::api::WatcherState>,
+
+    #[cfg(all(target_os = "android", feature = "encryption"))]
+    pub(crate) encryption_key: std::sync::Mutex<Option<[u8; 32]>>,
+
     #[cfg(not(target_os = "android"))]
     _marker: std::marker::PhantomData<fn() -> R>
 }
 
+/// Callback registered by [`AndroidFs::on_storage_volume_changed`].
+#[cfg(target_os = "android")]
+type StorageVolumeCallback = std::sync::Arc<dyn Fn(&StorageVolume) + Send + Sync>;
+
 impl<R: tauri::Runtime> AndroidFs<R> {
 
     pub(crate) fn new<C: serde::de::DeserializeOwned>(
@@ -37,7 +72,18 @@ impl<R: tauri::Runtime> AndroidFs<R> {
             Ok(Self {
                 api: api.register_android_plugin("com.plugin.android_fs", "AndroidFsPlugin")?, 
                 app,
-                intent_lock: std::sync::Mutex::new(())
+                intent_lock: std::sync::Mutex::new(()),
+                thumbnail_cache_state: std::sync::Mutex::new(Default::default()),
+                permission_registry_state: std::sync::Mutex::new(Default::default()),
+                incoming_intents_state: std::sync::Mutex::new(Default::default()),
+                cache_manager_state: std::sync::Mutex::new(Default::default()),
+                store_state: std::sync::Mutex::new(Default::default()),
+                storage_volume_callbacks: std::sync::Mutex::new(Default::default()),
+                webview_protocol_exposed: std::sync::Mutex::new(Default::default()),
+                #[cfg(feature = "watch")]
+                watcher_state: std::sync::Mutex::new(Default::default()),
+                #[cfg(feature = "encryption")]
+                encryption_key: std::sync::Mutex::new(None),
             })
         }
         
@@ -247,6 +293,30 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
+    /// Same as [`AndroidFs::write`], but with options.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file URI.  
+    /// This needs to be **writable**.
+    /// 
+    /// - ***options*** :  
+    /// See [`WriteOptions`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn write_with(&self, uri: &FileUri, contents: impl AsRef<[u8]>, options: &WriteOptions) -> crate::Result<()> {
+        on_android!({
+            let contents = contents.as_ref();
+
+            if options.ensure_space {
+                self.ensure_space(uri, contents.len() as u64)?;
+            }
+
+            self.write(uri, contents)
+        })
+    }
+
     /// Writes a slice as the entire contents of a file.  
     /// This function will entirely replace its contents if it does exist.    
     /// 
@@ -279,8 +349,7 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     /// Use this if you want to write using `std::fs::File`, not entire contents.
     /// 
     /// If you want to retain the file outside the closure, 
-    /// you can perform the same operation using [`AndroidFs::copy_via_kotlin`] and [`PrivateStorage`]. 
-    /// For details, please refer to the internal implementation of this function.
+    /// you can perform the same operation using [`PrivateStorage::create_temp_file`] and [`TempFile::persist`].
     /// 
     /// # Args
     /// - ***uri*** :  
@@ -296,34 +365,10 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     ) -> crate::Result<T> {
 
         on_android!({
-            let tmp_file_path = {
-                use std::sync::atomic::{AtomicUsize, Ordering};
-
-                static COUNTER: AtomicUsize = AtomicUsize::new(0);
-                let id = COUNTER.fetch_add(1, Ordering::Relaxed);
-
-                self.private_storage().resolve_path_with(
-                    PrivateDir::Cache,
-                    format!("{TMP_DIR_RELATIVE_PATH}/write_via_kotlin_in {id}")
-                )?
-            };
-
-            if let Some(parent) = tmp_file_path.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-
-            let result = {
-                let ref mut file = std::fs::File::create(&tmp_file_path)?;
-                contents_writer(file)
-            };
-
-            let result = result
-                .map_err(crate::Error::from)
-                .and_then(|t| self.copy_via_kotlin(&(&tmp_file_path).into(), uri).map(|_| t));
-
-            let _ = std::fs::remove_file(&tmp_file_path);
-
-            result
+            let mut tmp_file = self.private_storage().create_temp_file_for("write_via_kotlin_in")?;
+            let result = contents_writer(tmp_file.as_file_mut())?;
+            tmp_file.persist(uri)?;
+            Ok(result)
         })
     }
 
@@ -368,6 +413,115 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
+    /// Same as [`AndroidFs::copy_via_kotlin`], but with options.
+    /// 
+    /// # Args
+    /// - ***src*** :  
+    /// The URI of source file.  
+    /// This needs to be **readable**.
+    /// 
+    /// - ***dest*** :  
+    /// The URI of destination file.  
+    /// This needs to be **writable**.
+    /// 
+    /// - ***options*** :  
+    /// See [`WriteOptions`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn copy_via_kotlin_with(&self, src: &FileUri, dest: &FileUri, options: &WriteOptions) -> crate::Result<()> {
+        on_android!({
+            if options.ensure_space {
+                let len = self.get_metadata(src)?.len();
+                self.ensure_space(dest, len)?;
+            }
+
+            self.copy_via_kotlin(src, dest)
+        })
+    }
+
+    /// Gets the number of bytes that can be written to the storage of the target.
+    /// 
+    /// For `PrivateDir`, `PublicDir` and `file://` URIs,  
+    /// this includes the space that the system can free by clearing the cache of apps.  
+    /// To actually free it before writing, use [`AndroidFs::reserve_space`].  
+    /// For content URIs, this is the free space of the storage on which the file is located.
+    /// 
+    /// # Args
+    /// - ***target*** :  
+    /// See [`SpaceTarget`].
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/os/storage/StorageManager#getAllocatableBytes(java.util.UUID)>
+    pub fn available_space<'a>(&self, target: impl Into<SpaceTarget<'a>>) -> crate::Result<u64> {
+        on_android!({
+            impl_se!(struct Req<'a> { target: &'static str, path: Option<String>, uri: Option<&'a FileUri> });
+            impl_de!(struct Res { value: u64 });
+
+            let req = match target.into() {
+                SpaceTarget::PrivateDir(dir) => Req {
+                    target: "path",
+                    path: Some(self.private_storage().resolve_path(dir)?.to_string_lossy().into_owned()),
+                    uri: None,
+                },
+                SpaceTarget::PublicDir(_) => Req { target: "public", path: None, uri: None },
+                SpaceTarget::Uri(uri) => Req { target: "uri", path: None, uri: Some(uri) },
+            };
+
+            self.api
+                .run_mobile_plugin::<Res>("getAvailableSpace", req)
+                .map(|v| v.value)
+                .map_err(Into::into)
+        })
+    }
+
+    /// Reserves the space of the file in advance, so that writing up to ***bytes*** does not fail due to lack of space.  
+    /// If needed, the system frees the space by clearing the cache of apps.
+    /// 
+    /// The existing contents of the file are not changed.  
+    /// However, the space is allocated by `fallocate`,  
+    /// so if the file is shorter than ***bytes***, it is extended to ***bytes*** and the extended part is filled with zeros.  
+    /// If you write less than that, truncate the file to the written length afterwards.  
+    /// If there is not enough space even after clearing, an error is returned.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file URI.  
+    /// This needs to be **writable**.
+    /// 
+    /// - ***bytes*** :  
+    /// Number of bytes to reserve.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/os/storage/StorageManager#allocateBytes(java.io.FileDescriptor,%20long)>
+    pub fn reserve_space(&self, uri: &FileUri, bytes: u64) -> crate::Result<()> {
+        on_android!({
+            impl_se!(struct Req<'a> { uri: &'a FileUri, bytes: u64 });
+            impl_de!(struct Res;);
+
+            self.api
+                .run_mobile_plugin::<Res>("reserveSpace", Req { uri, bytes })
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+    }
+
+    /// Returns [`crate::Error::InsufficientSpace`] if less than ***required*** bytes can be written to the target.
+    #[cfg(target_os = "android")]
+    pub(crate) fn ensure_space<'a>(&self, target: impl Into<SpaceTarget<'a>>, required: u64) -> crate::Result<()> {
+        let available = self.available_space(target)?;
+        if available < required {
+            return Err(crate::Error::InsufficientSpace { required, available })
+        }
+        Ok(())
+    }
+
     /// Remove the file.
     /// 
     /// # Args
@@ -458,14 +612,11 @@ impl<R: tauri::Runtime> AndroidFs<R> {
             });
             impl_de!(struct Res { value: bool });
 
-            let (quality, format) = match format {
-                DecodeOption::Png => (1.0, "Png"),
-                DecodeOption::Jpeg => (0.75, "Jpeg"),
-                DecodeOption::Webp => (0.7, "Webp"),
-                DecodeOption::JpegWith { quality } => (quality, "Jpeg"),
-                DecodeOption::WebpWith { quality } => (quality, "Webp"),
-            };
-            let quality = (quality * 100.0).clamp(0.0, 100.0) as u8;
+            if !self.is_image_format_available(&format)? {
+                return Err(crate::Error::UnsupportedImageFormat(format))
+            }
+
+            let (format, quality) = format.to_plugin_args();
             let Size { width, height } = preferred_size;
         
             self.api
@@ -478,7 +629,7 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     /// Query the provider to get a file thumbnail.  
     /// If thumbnail does not exist it, return None.
     /// 
-    /// Note this does not cache. Please do it in your part if need.  
+    /// Note this does not cache. If need, use [`ThumbnailCache::get_thumbnail`] instead.
     /// 
     /// # Args
     /// - ***uri*** :  
@@ -509,39 +660,259 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     ) -> crate::Result<Option<Vec<u8>>> {
 
         on_android!({
-            let tmp_file_path = {
-                use std::sync::atomic::{AtomicUsize, Ordering};
+            let tmp_file = self.private_storage().create_temp_file_for("get_thumbnail")?;
 
-                static COUNTER: AtomicUsize = AtomicUsize::new(0);
-                let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+            if self.get_thumbnail_to(uri, &tmp_file.uri(), preferred_size, format)? {
+                return Ok(Some(std::fs::read(tmp_file.path())?))
+            }
 
-                self.private_storage().resolve_path_with(
-                    PrivateDir::Cache,
-                    format!("{TMP_DIR_RELATIVE_PATH}/get_thumbnail {id}")
-                )?
-            };
+            Ok(None)
+        })
+    }
+
+    /// Query the provider to get thumbnails of multiple files in a single call.
+    /// 
+    /// Thumbnails are generated in parallel on Kotlin side,  
+    /// and the returned iterator yields each result as soon as it finishes.  
+    /// So the order of the results is not guaranteed.  
+    /// Failures are reported per file, and do not stop the others.
+    /// 
+    /// See [`AndroidFs::get_thumbnail`] for details of the args.
+    /// 
+    /// # Note
+    /// The iterator blocks until the next result is available.  
+    /// So consider iterating it in a thread other than the main thread.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_thumbnails(
+        &self,
+        uris: &[FileUri],
+        preferred_size: Size,
+        format: ImageFormat,
+    ) -> crate::Result<impl Iterator<Item = (FileUri, crate::Result<Option<Vec<u8>>>)>> {
+
+        on_android!(std::iter::Empty::<_>, {
+            impl_se!(struct Req {
+                srcs: Vec<FileUri>,
+                dest_dir: String,
+                format: &'static str,
+                quality: u8,
+                width: u32,
+                height: u32,
+                channel: tauri::ipc::Channel,
+            });
+            impl_de!(struct Res;);
+            impl_de!(struct Msg { index: usize, value: Option<bool>, error: Option<String> });
+
+            enum Event {
+                Item(Msg),
+                Done(crate::Result<()>),
+            }
 
-            if let Some(parent) = tmp_file_path.parent() {
-                let _ = std::fs::create_dir_all(parent);
+            if !self.is_image_format_available(&format)? {
+                return Err(crate::Error::UnsupportedImageFormat(format))
             }
 
-            std::fs::File::create(&tmp_file_path)?;
+            let tmp_dir = self.private_storage().create_temp_dir_for("get_thumbnails")?;
 
-            let result = self.get_thumbnail_to(uri, &(&tmp_file_path).into(), preferred_size, format)
-                .and_then(|ok| {
-                    if (ok) {
-                        std::fs::read(&tmp_file_path)
-                            .map(Some)
-                            .map_err(Into::into)
+            let (tx, rx) = std::sync::mpsc::channel::<Event>();
+            let channel = {
+                let tx = tx.clone();
+                tauri::ipc::Channel::new(move |body| {
+                    if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                        if let Ok(msg) = serde_json::from_str::<Msg>(&json) {
+                            let _ = tx.send(Event::Item(msg));
+                        }
                     }
-                    else {
-                        Ok(None)
+                    Ok(())
+                })
+            };
+
+            let (format, quality) = format.to_plugin_args();
+            let Size { width, height } = preferred_size;
+            let req = Req {
+                srcs: uris.to_vec(),
+                dest_dir: tmp_dir.path().to_string_lossy().into_owned(),
+                format,
+                quality,
+                width,
+                height,
+                channel,
+            };
+
+            let api = self.api.clone();
+            std::thread::spawn(move || {
+                let result = api
+                    .run_mobile_plugin::<Res>("getThumbnails", req)
+                    .map(|_| ())
+                    .map_err(Into::into);
+
+                let _ = tx.send(Event::Done(result));
+            });
+
+            // Channel messages are not ordered with respect to the resolution of the call,  
+            // so results that arrive shortly after it are still reported.
+            const GRACE_PERIOD: std::time::Duration = std::time::Duration::from_millis(500);
+
+            let uris = uris.to_vec();
+            let mut remaining = (0..uris.len()).collect::<std::collections::BTreeSet<_>>();
+            let mut batch_error = None::<(String, std::time::Instant)>;
+
+            Ok(std::iter::from_fn(move || {
+                loop {
+                    if remaining.is_empty() {
+                        return None
+                    }
+
+                    let event = match &batch_error {
+                        Some((message, deadline)) => {
+                            let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+
+                            match rx.recv_timeout(timeout) {
+                                Ok(event) => Ok(event),
+                                Err(_) => {
+                                    // If the whole call failed, report the error for the unprocessed files.
+                                    let i = remaining.pop_first()?;
+                                    return Some((uris[i].clone(), Err(crate::Error::PluginInvoke(String::clone(message)))))
+                                },
+                            }
+                        },
+                        None => rx.recv(),
+                    };
+
+                    match event {
+                        Ok(Event::Item(msg)) => {
+                            if !remaining.remove(&msg.index) {
+                                continue
+                            }
+
+                            let path = tmp_dir.path().join(msg.index.to_string());
+                            let result = match (msg.value, msg.error) {
+                                (_, Some(error)) => Err(crate::Error::PluginInvoke(error)),
+                                (Some(true), None) => std::fs::read(&path).map(Some).map_err(Into::into),
+                                _ => Ok(None),
+                            };
+                            let _ = std::fs::remove_file(&path);
+
+                            return Some((uris[msg.index].clone(), result))
+                        },
+                        Ok(Event::Done(Ok(_))) => {
+                            batch_error = Some(("Thumbnail was not reported.".to_string(), std::time::Instant::now() + GRACE_PERIOD));
+                        },
+                        Ok(Event::Done(Err(e))) => {
+                            batch_error = Some((e.to_string(), std::time::Instant::now() + GRACE_PERIOD));
+                        },
+                        Err(_) => {
+                            batch_error = Some(("Thumbnail was not reported.".to_string(), std::time::Instant::now()));
+                        },
                     }
-                });
+                }
+            }))
+        })
+    }
+
+    /// Decodes an image or a video frame, resizes it, and re-encodes it to dest.
+    /// 
+    /// Unlike [`AndroidFs::get_thumbnail_to`], this decodes the source itself instead of the thumbnail by the provider,  
+    /// so the result has exactly the requested size.  
+    /// This can be used, for example, to downscale camera photos before upload.
+    /// 
+    /// # Args
+    /// - ***src*** :  
+    /// The URI of source image or video file.  
+    /// This needs to be **readable**.  
+    /// If video, the frame near the middle is used.
+    /// 
+    /// - ***dest*** :  
+    /// The URI of destination file.  
+    /// This needs to be **writable**.  
+    /// The existing contents is truncated.
+    /// 
+    /// - ***options*** :  
+    /// Size, format and metadata handling of the result.  
+    /// See [`TranscodeOptions`] for details.
+    /// 
+    /// # Support
+    /// All.
+    pub fn transcode_image(
+        &self,
+        src: &FileUri,
+        dest: &FileUri,
+        options: TranscodeOptions,
+    ) -> crate::Result<()> {
+
+        on_android!({
+            impl_se!(struct Req<'a> {
+                src: &'a FileUri,
+                dest: &'a FileUri,
+                format: &'a str,
+                quality: u8,
+                max_size: Option<Size>,
+                exact_size: Option<Size>,
+                fit: ImageFit,
+                strip_metadata: bool,
+                respect_exif_orientation: bool,
+            });
+            impl_de!(struct Res;);
+
+            if !self.is_image_format_available(&options.format)? {
+                return Err(crate::Error::UnsupportedImageFormat(options.format))
+            }
+
+            let (format, quality) = options.format.to_plugin_args();
+            let TranscodeOptions { max_size, exact_size, fit, strip_metadata, respect_exif_orientation, .. } = options;
+
+            self.api
+                .run_mobile_plugin::<Res>("transcodeImage", Req {
+                    src,
+                    dest,
+                    format,
+                    quality,
+                    max_size,
+                    exact_size,
+                    fit,
+                    strip_metadata,
+                    respect_exif_orientation,
+                })
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+    }
+
+    /// Verify whether the specified image format can be encoded on a given device.
+    /// 
+    /// [`ImageFormat::Heic`] and [`ImageFormat::Avif`] depend on the hardware encoders of the device.  
+    /// Others are available in all devices.  
+    /// If not available, [`AndroidFs::get_thumbnail_to`], [`AndroidFs::get_thumbnail`] and [`AndroidFs::transcode_image`]  
+    /// return [`Error::UnsupportedImageFormat`](crate::Error::UnsupportedImageFormat).
+    /// 
+    /// # Support
+    /// All.
+    pub fn is_image_format_available(&self, format: &ImageFormat) -> crate::Result<bool> {
+        on_android!({
+            impl_de!(struct Res { png: bool, jpeg: bool, webp: bool, webp_lossless: bool, heic: bool, avif: bool });
 
-            let _ = std::fs::remove_file(&tmp_file_path);
+            static FORMATS: std::sync::OnceLock<Res> = std::sync::OnceLock::new();
 
-            result
+            if FORMATS.get().is_none() {
+                let formats = self.api
+                    .run_mobile_plugin::<Res>("getAvailableImageFormats", "")?;
+
+                let _ = FORMATS.set(formats);
+            }
+
+            let formats = FORMATS.get().unwrap();
+
+            Ok(match format.to_plugin_args().0 {
+                "Png" => formats.png,
+                "Jpeg" => formats.jpeg,
+                "Webp" => formats.webp,
+                "WebpLossless" => formats.webp_lossless,
+                "Heic" => formats.heic,
+                "Avif" => formats.avif,
+                _ => false,
+            })
         })
     }
 
@@ -691,6 +1062,110 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
+    /// Same as [`AndroidFs::show_open_file_dialog`],  
+    /// but takes [`PickerOptions`] and returns the name, MIME type, size and last modified time with each URI.  
+    /// These are retrieved at once, so there is no need to call [`AndroidFs::get_name`] etc. for each file.
+    /// 
+    /// If no file is selected or the user cancels, an empty vec is returned.
+    /// 
+    /// # Args
+    /// - ***options*** :  
+    /// See [`PickerOptions`].  
+    /// This picker can not limit the number of selection,  
+    /// so if more than ***options.max_items*** files are selected, the excess files are discarded.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/content/Intent#ACTION_OPEN_DOCUMENT>
+    pub fn show_open_file_dialog_with(&self, options: &PickerOptions) -> crate::Result<Vec<PickedFile>> {
+        on_android!({
+            impl_se!(struct Req<'a> { options: PickerReq<'a> });
+
+            self.show_picker_dialog("showOpenFileDialogWith", Req { options: PickerReq::new(options) })
+        })
+    }
+
+    /// Same as [`AndroidFs::show_open_content_dialog`],  
+    /// but takes [`PickerOptions`] and returns the name, MIME type, size and last modified time with each URI.
+    /// 
+    /// If no file is selected or the user cancels, an empty vec is returned.
+    /// 
+    /// # Args
+    /// - ***options*** :  
+    /// See [`PickerOptions`].  
+    /// ***options.initial_location*** is ignored.  
+    /// This picker can not limit the number of selection,  
+    /// so if more than ***options.max_items*** files are selected, the excess files are discarded.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/content/Intent#ACTION_GET_CONTENT>
+    pub fn show_open_content_dialog_with(&self, options: &PickerOptions) -> crate::Result<Vec<PickedFile>> {
+        on_android!({
+            impl_se!(struct Req<'a> { options: PickerReq<'a> });
+
+            self.show_picker_dialog("showOpenContentDialogWith", Req { options: PickerReq::new(options) })
+        })
+    }
+
+    /// Same as [`AndroidFs::show_open_visual_media_dialog`],  
+    /// but takes [`PickerOptions`] and returns the name, MIME type, size and last modified time with each URI.
+    /// 
+    /// If no file is selected or the user cancels, an empty vec is returned.
+    /// 
+    /// # Args
+    /// - ***target*** :  
+    /// The media type of the file to be selected.
+    /// 
+    /// - ***options*** :  
+    /// See [`PickerOptions`].  
+    /// ***options.mime_types*** is ignored, and ***target*** is used instead.  
+    /// ***options.local_only*** and ***options.initial_location*** are used only if the media picker is unavailable.  
+    /// ***options.ordered*** is used only if the media picker is available.
+    /// 
+    /// # Support
+    /// All.  
+    /// See [`AndroidFs::show_open_visual_media_dialog`] for details.
+    /// 
+    /// # References
+    /// <https://developer.android.com/training/data-storage/shared/photopicker>
+    pub fn show_open_visual_media_dialog_with(
+        &self,
+        target: VisualMediaTarget,
+        options: &PickerOptions,
+    ) -> crate::Result<Vec<PickedFile>> {
+
+        on_android!({
+            impl_se!(struct Req<'a> { target: &'a str, mime_type: Option<&'a str>, options: PickerReq<'a> });
+
+            let (target, mime_type) = target.to_plugin_args();
+
+            self.show_picker_dialog("showOpenVisualMediaDialogWith", Req { target, mime_type, options: PickerReq::new(options) })
+        })
+    }
+
+    #[cfg(target_os = "android")]
+    fn show_picker_dialog(&self, command: &str, req: impl serde::Serialize) -> crate::Result<Vec<PickedFile>> {
+        impl_de!(struct Obj { uri: FileUri, name: String, mime_type: Option<String>, len: Option<u64>, last_modified: Option<u64> });
+        impl_de!(struct Res { items: Vec<Obj> });
+
+        let _guard = self.intent_lock.lock();
+        self.api
+            .run_mobile_plugin::<Res>(command, req)
+            .map(|v| v.items.into_iter().map(|v| PickedFile {
+                uri: v.uri,
+                name: v.name,
+                mime_type: v.mime_type,
+                len: v.len,
+                last_modified: v.last_modified.map(|t| std::time::UNIX_EPOCH + std::time::Duration::from_millis(t)),
+            }).collect())
+            .map_err(Into::into)
+    }
+
     /// Opens a file picker and returns a **readonly** URIs.  
     /// If no file is selected or the user cancels, an empty vec is returned.  
     ///  
@@ -772,12 +1247,14 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     ) -> crate::Result<Vec<FileUri>> {
 
         on_android!({
-            impl_se!(struct Req { multiple: bool, target: VisualMediaTarget });
+            impl_se!(struct Req<'a> { multiple: bool, target: &'a str, mime_type: Option<&'a str> });
             impl_de!(struct Res { uris: Vec<FileUri> });
+
+            let (target, mime_type) = target.to_plugin_args();
     
             let _guard = self.intent_lock.lock();
             self.api
-                .run_mobile_plugin::<Res>("showOpenVisualMediaDialog", Req { multiple, target })
+                .run_mobile_plugin::<Res>("showOpenVisualMediaDialog", Req { multiple, target, mime_type })
                 .map(|v| v.uris)
                 .map_err(Into::into)
         })
@@ -829,12 +1306,65 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
-    /// Acquire manage external storage permission.
-    ///
+    /// Opens a system directory picker and returns the permission of the selected directory.  
+    /// If no directory is selected or the user cancels, `None` is returned.
+    /// 
+    /// Unlike [`AndroidFs::show_manage_dir_dialog`], this can limit the access to read-only,  
+    /// and persists the permission automatically if ***options.persist*** is true.  
+    /// The system always grants read-write access to the directory selected in this dialog,  
+    /// so read-only is achieved by reporting and persisting only [`PersistableAccessMode::Read`].  
+    /// Therefore, the confirmation shown by the system is the same as that of [`AndroidFs::show_manage_dir_dialog`].
+    /// 
+    /// # Args
+    /// - ***options*** :  
+    /// See [`DirDialogOptions`].
+    /// 
     /// # Support
     /// All.
-    pub fn acquire_manage_external_storage(&self) -> crate::Result<()> {
-        on_android!({
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/content/Intent#ACTION_OPEN_DOCUMENT_TREE>
+    pub fn show_open_dir_dialog_with(
+        &self,
+        options: DirDialogOptions,
+    ) -> crate::Result<Option<PersistedUriPermission>> {
+
+        on_android!({
+            impl_se!(struct Req<'a> { initial_location: Option<&'a FileUri> });
+            impl_de!(struct Res { uri: Option<FileUri>, r: bool, w: bool });
+
+            let res = {
+                let _guard = self.intent_lock.lock();
+                self.api.run_mobile_plugin::<Res>("showManageDirDialog", Req {
+                    initial_location: options.initial_location.as_ref(),
+                })?
+            };
+
+            let Some(uri) = res.uri else {
+                return Ok(None)
+            };
+
+            let can_read = res.r;
+            let can_write = res.w && !options.read_only;
+
+            if options.persist {
+                let mode = match can_write {
+                    true => PersistableAccessMode::ReadAndWrite,
+                    false => PersistableAccessMode::Read,
+                };
+                self.take_persistable_uri_permission_with(&uri, mode)?;
+            }
+
+            Ok(Some(PersistedUriPermission::Dir { uri, can_read, can_write }))
+        })
+    }
+
+    /// Acquire manage external storage permission.
+    ///
+    /// # Support
+    /// All.
+    pub fn acquire_manage_external_storage(&self) -> crate::Result<()> {
+        on_android!({
             impl_de!(struct Res { value: bool });
 
             self.api
@@ -934,6 +1464,70 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
+    /// Opens a system directory picker to save multiple files at once,  
+    /// and returns the **read-write** URIs of the created files in the same order as ***files***.  
+    /// The created files have no content.  
+    /// If the user cancels, `None` is returned.
+    /// 
+    /// Each file is created in the selected directory by [`AndroidFs::create_file`].  
+    /// If a file with the same name already exists or the same name is specified multiple times,  
+    /// a sequential number is appended to the name, such as `report (1).pdf`.  
+    /// If creating any file fails, the files already created by this are removed and the error is returned.
+    /// 
+    /// By default, returned URIs are valid until the app is terminated.  
+    /// If you want to persist them across app restarts, use [`AndroidFs::take_persistable_uri_permission`].
+    /// 
+    /// # Args
+    /// - ***files*** :  
+    /// The file names and MIME types of the files to be saved.  
+    /// If MIME type is None, it is inferred from the extension of the file name  
+    /// and if that fails, `application/octet-stream` is used.
+    /// 
+    /// - ***initial_location*** :  
+    /// Indicate the initial location of dialog.  
+    /// See [`AndroidFs::show_manage_dir_dialog`] for details.
+    /// 
+    /// # Support
+    /// All.
+    pub fn show_save_files_dialog(
+        &self,
+        files: &[(&str, Option<&str>)],
+        initial_location: Option<&FileUri>,
+    ) -> crate::Result<Option<Vec<FileUri>>> {
+
+        on_android!({
+            let Some(dir) = self.show_manage_dir_dialog(initial_location)? else {
+                return Ok(None)
+            };
+
+            let mut taken = self.read_dir(&dir)?
+                .map(|e| match e {
+                    Entry::File { name, .. } => name,
+                    Entry::Dir { name, .. } => name,
+                })
+                .collect::<std::collections::HashSet<_>>();
+
+            let mut uris = Vec::with_capacity(files.len());
+            for (name, mime_type) in files {
+                let name = unique_file_name(name, &taken);
+
+                match self.create_file(&dir, &name, *mime_type) {
+                    Ok(uri) => uris.push(uri),
+                    Err(e) => {
+                        for uri in &uris {
+                            let _ = self.remove_file(uri);
+                        }
+                        return Err(e)
+                    }
+                }
+
+                taken.insert(name);
+            }
+
+            Ok(Some(uris))
+        })
+    }
+
     /// Create an **restricted** URI for the specified directory.  
     /// 
     /// This should only be used as `initial_location` in the dialog. 
@@ -971,6 +1565,18 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     ///         true // Create dirs of 'sub_dir1' and 'sub_dir2', if not exists
     ///     ).expect("Should be on Android");
     ///
+    ///     // Get URI of the SD card's DCIM/
+    ///     let volumes = api.list_storage_volumes().expect("Should be on Android");
+    ///     if let Some(volume) = volumes.iter().find(|v| v.removable) {
+    ///         let initial_location = api.resolve_initial_location(
+    ///             InitialLocation::Volume {
+    ///                 id: &volume.id,
+    ///                 relative_path: "DCIM"
+    ///             },
+    ///             false
+    ///         ).expect("Should be on Android");
+    ///     }
+    ///
     ///     // Open dialog with initial_location
     ///     let _ = api.show_save_file_dialog(Some(&initial_location), "", None);
     ///     let _ = api.show_open_file_dialog(Some(&initial_location), &[], true);
@@ -987,6 +1593,7 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     ) -> crate::Result<FileUri> {
 
         on_android!({
+            const DOCUMENT_URI_PREFIX: &str = "content://com.android.externalstorage.documents/document/";
             const TOP_DIR: &str = "content://com.android.externalstorage.documents/document/primary%3A";
 
             let uri = match dir.into() {
@@ -1008,13 +1615,88 @@ impl<R: tauri::Runtime> AndroidFs<R> {
                         let sub_dirs = relative_path.replace("/", "%2F");
                         format!("{TOP_DIR}{base_dir}%2F{sub_dirs}")
                     }
-                }
+                },
+                InitialLocation::Volume { id, relative_path } => {
+                    let relative_path = relative_path.trim_matches('/').replace("/", "%2F");
+                    format!("{DOCUMENT_URI_PREFIX}{id}%3A{relative_path}")
+                },
             };
 
             Ok(FileUri { uri, document_top_tree_uri: None })
         })
     }
 
+    /// Gets the storage volumes, such as internal shared storage, SD card and USB drive.  
+    /// Volumes that are not mounted are also included.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/os/storage/StorageManager#getStorageVolumes()>
+    pub fn list_storage_volumes(&self) -> crate::Result<Vec<StorageVolume>> {
+        on_android!({
+            impl_de!(struct Res { volumes: Vec<StorageVolume> });
+
+            self.api
+                .run_mobile_plugin::<Res>("listStorageVolumes", "")
+                .map(|v| v.volumes)
+                .map_err(Into::into)
+        })
+    }
+
+    /// Registers a callback that is called each time the state of a storage volume is changed,  
+    /// such as SD card being mounted or unmounted.
+    /// 
+    /// The event [`STORAGE_VOLUME_CHANGED_EVENT`] is also emitted to the frontend.
+    /// 
+    /// # Support
+    /// All.
+    pub fn on_storage_volume_changed(&self, callback: impl Fn(&StorageVolume) + Send + Sync + 'static) -> crate::Result<()> {
+        on_android!({
+            self.storage_volume_callbacks
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(std::sync::Arc::new(callback));
+
+            Ok(())
+        })
+    }
+
+    /// Starts receiving the changes of storage volumes from the plugin.  
+    /// This is called once when the plugin is initialized.
+    #[cfg(target_os = "android")]
+    pub(crate) fn listen_storage_volumes(&self) -> crate::Result<()> {
+        use tauri::Emitter as _;
+
+        impl_se!(struct Req { channel: tauri::ipc::Channel });
+        impl_de!(struct Res;);
+
+        let app = self.app.clone();
+        let channel = tauri::ipc::Channel::new(move |body| {
+            if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                if let Ok(volume) = serde_json::from_str::<StorageVolume>(&json) {
+                    let callbacks = app.android_fs().storage_volume_callbacks
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .clone();
+
+                    for callback in callbacks {
+                        callback(&volume);
+                    }
+
+                    let _ = app.emit(STORAGE_VOLUME_CHANGED_EVENT, &volume);
+                }
+            }
+            Ok(())
+        });
+
+        self.api
+            .run_mobile_plugin::<Res>("registerStorageVolumeListener", Req { channel })
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
     /// Opens a dialog for sharing file to other apps.  
     /// 
     /// An error will occur if there is no app that can handle the request. 
@@ -1040,6 +1722,54 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
+    /// Opens a dialog for sharing files and text to other apps.
+    /// 
+    /// Unlike [`AndroidFs::show_share_file_dialog`], this can share multiple files with text and subject.  
+    /// If multiple files are specified, they are shared by `ACTION_SEND_MULTIPLE`.
+    /// 
+    /// An error will occur if there is no app that can handle the request.  
+    /// Please use [`AndroidFs::can_share`] to confirm.
+    /// 
+    /// # Args
+    /// - ***options*** :  
+    /// See [`ShareOptions`].  
+    /// At least one of ***options.uris*** and ***options.text*** must be specified.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/content/Intent#ACTION_SEND_MULTIPLE>
+    pub fn show_share_dialog(&self, options: &ShareOptions) -> crate::Result<()> {
+        on_android!({
+            impl_de!(struct Res;);
+
+            self.api
+                .run_mobile_plugin::<Res>("share", ShareReq::new(options))
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+    }
+
+    /// Determines whether the specified options can be used with [`AndroidFs::show_share_dialog`].
+    /// 
+    /// # Args
+    /// - ***options*** :  
+    /// See [`ShareOptions`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn can_share(&self, options: &ShareOptions) -> crate::Result<bool> {
+        on_android!({
+            impl_de!(struct Res { value: bool });
+
+            self.api
+                .run_mobile_plugin::<Res>("canShare", ShareReq::new(options))
+                .map(|v| v.value)
+                .map_err(Into::into)
+        })
+    }
+
     /// Opens a dialog for viewing file on other apps.  
     /// This performs the general "open file" action.
     /// 
@@ -1107,6 +1837,355 @@ impl<R: tauri::Runtime> AndroidFs<R> {
         })
     }
 
+    /// Opens a dialog for viewing file on other apps, with options.  
+    /// This performs the general "open file" action same as [`AndroidFs::show_view_file_dialog`].
+    /// 
+    /// An error will occur if there is no app that can handle the request.  
+    /// Please use [`AndroidFs::can_view_file`] or [`AndroidFs::get_view_apps`] to confirm.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file uri to view.  
+    /// This needs to be **readable**.  
+    /// This given from [`PrivateStorage`] or [`AndroidFs::show_open_visual_media_dialog`] ***cannot*** be used.
+    /// 
+    /// - ***options*** :  
+    /// See [`ViewOptions`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn show_view_dialog(&self, uri: &FileUri, options: &ViewOptions) -> crate::Result<()> {
+        on_android!({
+            impl_de!(struct Res;);
+
+            self.api
+                .run_mobile_plugin::<Res>("view", ViewReq::new(uri, options, None))
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+    }
+
+    /// Same as [`AndroidFs::show_view_dialog`],  
+    /// but ***on_chosen*** is called with the app the user has chosen.
+    /// 
+    /// This does not wait for the user to choose.  
+    /// ***on_chosen*** is called at most once, and is not called if the user cancels the dialog.  
+    /// If ***options.always_show_chooser*** is false,  
+    /// it is called only if the app to open can be determined without the chooser, such as by ***options.target_package***.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file uri to view.  
+    /// This needs to be **readable**.  
+    /// This given from [`PrivateStorage`] or [`AndroidFs::show_open_visual_media_dialog`] ***cannot*** be used.
+    /// 
+    /// - ***options*** :  
+    /// See [`ViewOptions`].
+    /// 
+    /// - ***on_chosen*** :  
+    /// Callback called with the chosen app.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/content/Intent#createChooser(android.content.Intent,%20java.lang.CharSequence,%20android.content.IntentSender)>
+    pub fn show_view_dialog_with_callback(
+        &self,
+        uri: &FileUri,
+        options: &ViewOptions,
+        on_chosen: impl FnOnce(AppComponent) + Send + 'static,
+    ) -> crate::Result<()> {
+
+        on_android!({
+            impl_de!(struct Res;);
+
+            let on_chosen = std::sync::Mutex::new(Some(on_chosen));
+            let channel = tauri::ipc::Channel::new(move |body| {
+                if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                    if let Ok(component) = serde_json::from_str::<AppComponent>(&json) {
+                        let on_chosen = on_chosen.lock().unwrap_or_else(|e| e.into_inner()).take();
+                        if let Some(on_chosen) = on_chosen {
+                            on_chosen(component);
+                        }
+                    }
+                }
+                Ok(())
+            });
+
+            self.api
+                .run_mobile_plugin::<Res>("view", ViewReq::new(uri, options, Some(channel)))
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+    }
+
+    /// Gets the apps that can open the file by [`AndroidFs::show_view_dialog`].  
+    /// This app itself is not included.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file uri.  
+    /// If this is None, apps are determined by only ***mime_type***.
+    /// 
+    /// - ***mime_type*** :  
+    /// MIME type of the target, such as `application/pdf`.  
+    /// If this is None, the MIME type of ***uri*** is used.  
+    /// If both are None, `*/*` is used.
+    /// 
+    /// - ***icon_size*** :  
+    /// Width and height of the icons in pixels.  
+    /// If this is None, icons are not obtained and [`AppInfo::icon`] is always None.
+    /// 
+    /// # Note
+    /// Since Android 11, the app needs to declare the visibility to other apps in `AndroidManifest.xml`.  
+    /// This plugin declares it for `ACTION_VIEW` intents, so no additional settings are needed.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/training/package-visibility>
+    pub fn get_view_apps(
+        &self,
+        uri: Option<&FileUri>,
+        mime_type: Option<&str>,
+        icon_size: Option<u32>,
+    ) -> crate::Result<Vec<AppInfo>> {
+
+        on_android!({
+            impl_se!(struct Req<'a> { uri: Option<&'a FileUri>, mime_type: Option<&'a str>, icon_size: u32, icon_dest_dir: Option<String> });
+            impl_de!(struct Res { apps: Vec<App> });
+            impl_de!(struct App { component: AppComponent, label: String, icon: Option<String> });
+
+            let tmp_dir = match icon_size {
+                Some(_) => Some(self.private_storage().create_temp_dir_for("get_view_apps")?),
+                None => None,
+            };
+
+            let req = Req {
+                uri,
+                mime_type,
+                icon_size: icon_size.unwrap_or(0),
+                icon_dest_dir: tmp_dir.as_ref().map(|d| d.path().to_string_lossy().into_owned()),
+            };
+
+            self.api
+                .run_mobile_plugin::<Res>("getViewApps", req)
+                .map_err(Into::into)
+                .map(|v| v.apps.into_iter()
+                    .map(|app| AppInfo {
+                        component: app.component,
+                        label: app.label,
+                        icon: app.icon.and_then(|path| std::fs::read(path).ok()),
+                    })
+                    .collect()
+                )
+        })
+    }
+
+    /// Starts watching the changes of the file or directory.  
+    /// This requires the `watch` feature.
+    /// 
+    /// The changes are emitted to the frontend as the event [`FILE_CHANGE_EVENT`],  
+    /// and passed to the callbacks registered by [`WatchHandle::on_change`].  
+    /// Successive changes of the same file within a short interval are coalesced into one.
+    /// 
+    /// Watching continues until the returned [`WatchHandle`] is dropped.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file or directory URI.  
+    /// `file://` URIs, such as those of [`PrivateStorage`], are watched by inotify.  
+    /// Content URIs, such as those picked by dialogs, are watched by `ContentObserver`.  
+    /// In that case, the changes are notified only if the provider supports it,  
+    /// and the kind of change may always be [`FileChangeKind::Modify`].
+    /// 
+    /// - ***recursive*** :  
+    /// Whether to also watch the descendants of the directory.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/database/ContentObserver>
+    #[cfg(feature = "watch")]
+    pub fn watch(&self, uri: &FileUri, recursive: bool) -> crate::Result<WatchHandle<R>> {
+        on_android!({
+            crate::api::watch(self, uri, recursive)
+        })
+    }
+
+    /// Creates an archive of the directory and its descendants, and writes it to the file.  
+    /// This requires the `archive` feature.
+    /// 
+    /// The contents are streamed, so large directories can be archived without loading them into memory.  
+    /// To report the progress, use [`AndroidFs::zip_dir_with_progress`].
+    /// 
+    /// # Args
+    /// - ***src_dir*** :  
+    /// The URI of the directory to archive, such as one picked by [`AndroidFs::show_manage_dir_dialog`].  
+    /// This needs to be **readable**.
+    /// 
+    /// - ***dest_file*** :  
+    /// The URI of the archive file, such as one picked by [`AndroidFs::show_save_file_dialog`].  
+    /// This needs to be **writable**.
+    /// 
+    /// - ***options*** :  
+    /// See [`ArchiveOptions`].
+    /// 
+    /// # Support
+    /// All.
+    #[cfg(feature = "archive")]
+    pub fn zip_dir(&self, src_dir: &FileUri, dest_file: &FileUri, options: &ArchiveOptions) -> crate::Result<()> {
+        self.zip_dir_with_progress(src_dir, dest_file, options, |_| {})
+    }
+
+    /// Same as [`AndroidFs::zip_dir`], but ***on_progress*** is called with [`ArchiveProgress`] as the entries are processed.
+    /// 
+    /// # Support
+    /// All.
+    #[cfg(feature = "archive")]
+    pub fn zip_dir_with_progress(
+        &self,
+        src_dir: &FileUri,
+        dest_file: &FileUri,
+        options: &ArchiveOptions,
+        mut on_progress: impl FnMut(&ArchiveProgress),
+    ) -> crate::Result<()> {
+
+        on_android!({
+            crate::api::zip_dir(self, src_dir, dest_file, options, &mut on_progress)
+        })
+    }
+
+    /// Extracts the files in the archive into the directory, and returns the URIs of the created files.  
+    /// This requires the `archive` feature.
+    /// 
+    /// The contents are streamed, so large archives can be extracted without loading them into memory.  
+    /// To report the progress, use [`AndroidFs::unzip_with_progress`].
+    /// 
+    /// Entries whose paths are absolute or contain `..` are rejected with an error, to prevent them from being written outside the directory.  
+    /// For zip, all paths are validated before anything is extracted.  
+    /// For tar, the files before the rejected entry remain extracted.  
+    /// Directories without files, symbolic links and other special entries are skipped.
+    /// 
+    /// # Args
+    /// - ***src*** :  
+    /// The URI of the archive file, such as one picked by [`AndroidFs::show_open_file_dialog`].  
+    /// This needs to be **readable**.
+    /// 
+    /// - ***dest_dir*** :  
+    /// The URI of the directory to extract into, such as one of [`PrivateStorage::resolve_uri`] or picked by [`AndroidFs::show_manage_dir_dialog`].  
+    /// This needs to be **read-write**.  
+    /// Files are created by [`AndroidFs::create_file`],  
+    /// so if a file with the same name already exists, a sequential number will be appended.
+    /// 
+    /// - ***options*** :  
+    /// See [`ArchiveOptions`].
+    /// 
+    /// # Support
+    /// All.
+    #[cfg(feature = "archive")]
+    pub fn unzip(&self, src: &FileUri, dest_dir: &FileUri, options: &ArchiveOptions) -> crate::Result<Vec<FileUri>> {
+        self.unzip_with_progress(src, dest_dir, options, |_| {})
+    }
+
+    /// Same as [`AndroidFs::unzip`], but ***on_progress*** is called with [`ArchiveProgress`] as the entries are processed.
+    /// 
+    /// # Support
+    /// All.
+    #[cfg(feature = "archive")]
+    pub fn unzip_with_progress(
+        &self,
+        src: &FileUri,
+        dest_dir: &FileUri,
+        options: &ArchiveOptions,
+        mut on_progress: impl FnMut(&ArchiveProgress),
+    ) -> crate::Result<Vec<FileUri>> {
+
+        on_android!({
+            crate::api::unzip(self, src, dest_dir, options, &mut on_progress)
+        })
+    }
+
+    /// Exports the files in the private directory to a new backup file in ***dest_dir***, and returns its URI.  
+    /// This requires the `backup` feature.
+    /// 
+    /// The backup is a tar.gz file named `<identifier>-backup-<unix time>.tar.gz`,  
+    /// containing the files and a manifest with their SHA-256 hashes, the app identifier and the app version.  
+    /// It can be restored by [`AndroidFs::import_private_data`].
+    /// 
+    /// The files used internally by this plugin are not included, except the values of [`Store`].  
+    /// Note that the files of [`EncryptedStorage`](crate::EncryptedStorage) are included as is,  
+    /// but the key is not, so they cannot be decrypted after being restored on another device.
+    /// 
+    /// # Args
+    /// - ***dir*** :  
+    /// The private directory to export.
+    /// 
+    /// - ***dest_dir*** :  
+    /// The URI of the directory to create the backup file in, such as one picked by [`AndroidFs::show_manage_dir_dialog`].  
+    /// This needs to be **read-write**.
+    /// 
+    /// - ***filter*** :  
+    /// A closure that accepts the path of each file relative to ***dir***, separated by `/`,  
+    /// and returns whether to include it.
+    /// 
+    /// # Support
+    /// All.
+    #[cfg(feature = "backup")]
+    pub fn export_private_data(
+        &self,
+        dir: PrivateDir,
+        dest_dir: &FileUri,
+        mut filter: impl FnMut(&str) -> bool,
+    ) -> crate::Result<FileUri> {
+
+        on_android!({
+            crate::api::export_private_data(self, dir, dest_dir, &mut filter)
+        })
+    }
+
+    /// Restores the files in the backup created by [`AndroidFs::export_private_data`] to the private directory.  
+    /// This requires the `backup` feature.
+    /// 
+    /// Before restoring anything, the entire backup is read to validate it.  
+    /// If the files do not match the hashes in the manifest, [`Error::InvalidBackup`](crate::Error::InvalidBackup) is returned.  
+    /// If the backup was created by another app, a newer version of this app, or a newer format,  
+    /// [`Error::IncompatibleBackup`](crate::Error::IncompatibleBackup) is returned.
+    /// 
+    /// Each file is replaced atomically, but the restoration as a whole is not,  
+    /// so some files may remain restored if an error occurs in the middle.  
+    /// However, with [`ImportConflictPolicy::ReplaceAll`], the existing files are replaced only after all files are restored.
+    /// 
+    /// # Args
+    /// - ***src*** :  
+    /// The URI of the backup file, such as one picked by [`AndroidFs::show_open_file_dialog`].  
+    /// This needs to be **readable**.
+    /// 
+    /// - ***dir*** :  
+    /// The private directory to restore to.
+    /// 
+    /// - ***conflict_policy*** :  
+    /// How to handle the files that already exist.  
+    /// See [`ImportConflictPolicy`].
+    /// 
+    /// # Support
+    /// All.
+    #[cfg(feature = "backup")]
+    pub fn import_private_data(
+        &self,
+        src: &FileUri,
+        dir: PrivateDir,
+        conflict_policy: ImportConflictPolicy,
+    ) -> crate::Result<PrivateDataImportResult> {
+
+        on_android!({
+            crate::api::import_private_data(self, src, dir, conflict_policy)
+        })
+    }
+
     /// Take persistent permission to access the file, directory and its descendants.  
     /// This is a prolongation of an already acquired permission, not the acquisition of a new one.  
     /// 
@@ -1114,10 +2193,14 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     /// 
     /// Note that [there is a limit to the total number of URI that can be made persistent by this function.](https://stackoverflow.com/questions/71099575/should-i-release-persistableuripermission-when-a-new-storage-location-is-chosen/71100621#71100621)  
     /// Therefore, it is recommended to relinquish the unnecessary persisted URI by [`AndroidFs::release_persisted_uri_permission`] or [`AndroidFs::release_all_persisted_uri_permissions`].  
+    /// The remaining capacity can be checked by [`AndroidFs::get_persisted_uri_permission_capacity`].  
     /// Persisted permissions may be relinquished by other apps, user, or by moving/removing entries.
     /// So check by [`AndroidFs::check_persisted_uri_permission`].  
     /// And you can retrieve the list of persisted uris using [`AndroidFs::get_all_persisted_uri_permissions`].
     /// 
+    /// If the limit is reached, a permission is relinquished according to [`PermissionRegistry::set_eviction_policy`] before taking the new one.  
+    /// If nothing can be relinquished, this returns [`Error::PersistedUriPermissionLimitReached`].
+    /// 
     /// # Args
     /// - **uri** :  
     /// URI of the target file or directory. This must be a URI taken from following :  
@@ -1136,10 +2219,49 @@ impl<R: tauri::Runtime> AndroidFs<R> {
             impl_se!(struct Req<'a> { uri: &'a FileUri });
             impl_de!(struct Res;);
 
-            self.api
-                .run_mobile_plugin::<Res>("takePersistableUriPermission", Req { uri })
-                .map(|_| ())
-                .map_err(Into::into)
+            self.permission_registry().take_with_eviction(uri, || {
+                self.api
+                    .run_mobile_plugin::<Res>("takePersistableUriPermission", Req { uri })
+                    .map(|_| ())
+                    .map_err(Into::into)
+            })
+        })
+    }
+
+    /// Take persistent permission of the specified mode to access the file, directory and its descendants.
+    /// 
+    /// Unlike [`AndroidFs::take_persistable_uri_permission`] that persists whatever was granted,  
+    /// this persists only the specified mode.  
+    /// So this can be used to persist only read access, even if write access was also granted.
+    /// 
+    /// See [`AndroidFs::take_persistable_uri_permission`] for details.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// URI of the target file or directory.  
+    /// See [`AndroidFs::take_persistable_uri_permission`] for details.
+    /// 
+    /// - ***mode*** :  
+    /// The mode of permission you want to persist.  
+    /// If this mode was not granted by the dialog that returned the URI, [`Error::UriPermissionNotGranted`] is returned.
+    /// 
+    /// # Support
+    /// All.
+    pub fn take_persistable_uri_permission_with(&self, uri: &FileUri, mode: PersistableAccessMode) -> crate::Result<()> {
+        on_android!({
+            impl_se!(struct Req<'a> { uri: &'a FileUri, mode: PersistableAccessMode });
+            impl_de!(struct Res { value: bool });
+
+            let granted = self.permission_registry().take_with_eviction(uri, || {
+                Ok(self.api
+                    .run_mobile_plugin::<Res>("takePersistableUriPermissionWith", Req { uri, mode })?
+                    .value)
+            })?;
+
+            match granted {
+                true => Ok(()),
+                false => Err(crate::Error::UriPermissionNotGranted(mode)),
+            }
         })
     }
 
@@ -1175,23 +2297,53 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     /// All.
     pub fn get_all_persisted_uri_permissions(&self) -> crate::Result<impl Iterator<Item = PersistedUriPermission>> {
         on_android!(std::iter::Empty::<_>, {
-            impl_de!(struct Obj { uri: FileUri, r: bool, w: bool, d: bool });
+            self.get_all_persisted_uri_permissions_with_time()
+                .map(|v| v.into_iter().map(|(p, _)| p))
+        })
+    }
+
+    /// Same as [`AndroidFs::get_all_persisted_uri_permissions`], but also returns the time when each permission was persisted.
+    pub(crate) fn get_all_persisted_uri_permissions_with_time(&self) -> crate::Result<Vec<(PersistedUriPermission, std::time::SystemTime)>> {
+        on_android!({
+            impl_de!(struct Obj { uri: FileUri, r: bool, w: bool, d: bool, t: u64 });
             impl_de!(struct Res { items: Vec<Obj> });
     
             self.api
                 .run_mobile_plugin::<Res>("getAllPersistedUriPermissions", "")
-                .map(|v| v.items.into_iter())
-                .map(|v| v.map(|v| {
+                .map(|v| v.items.into_iter().map(|v| {
                     let (uri, can_read, can_write) = (v.uri, v.r, v.w);
-                    match v.d {
+                    let time = std::time::UNIX_EPOCH + std::time::Duration::from_millis(v.t);
+                    let permission = match v.d {
                         true => PersistedUriPermission::Dir { uri, can_read, can_write },
                         false => PersistedUriPermission::File { uri, can_read, can_write }
-                    }
-                }))
+                    };
+                    (permission, time)
+                }).collect())
                 .map_err(Into::into)
         })
     }
 
+    /// Get the number of URI permissions persisted by [`AndroidFs::take_persistable_uri_permission`] and its limit.
+    /// 
+    /// Once the limit is reached, [`AndroidFs::take_persistable_uri_permission`] fails silently.  
+    /// To relinquish old permissions automatically, consider using [`PermissionRegistry::set_eviction_policy`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_persisted_uri_permission_capacity(&self) -> crate::Result<PersistedUriPermissionCapacity> {
+        on_android!({
+            impl_de!(struct Res { value: usize });
+
+            let limit = self.api
+                .run_mobile_plugin::<Res>("getPersistedUriPermissionLimit", "")?
+                .value;
+
+            let used = self.get_all_persisted_uri_permissions_with_time()?.len();
+
+            Ok(PersistedUriPermissionCapacity { limit, used })
+        })
+    }
+
     /// Relinquish a persisted URI permission grant by [`AndroidFs::take_persistable_uri_permission`].   
     /// 
     /// # Args
@@ -1251,4 +2403,116 @@ impl<R: tauri::Runtime> AndroidFs<R> {
     pub fn public_storage(&self) -> PublicStorage<'_, R> {
         PublicStorage(self)
     }
-}
\ No newline at end of file
+
+    /// Thumbnail cache stored in [`PrivateDir::Cache`].
+    pub fn thumbnail_cache(&self) -> ThumbnailCache<'_, R> {
+        ThumbnailCache(self)
+    }
+
+    /// Registry of persisted URI permissions with app-defined keys.
+    pub fn permission_registry(&self) -> PermissionRegistry<'_, R> {
+        PermissionRegistry(self)
+    }
+
+    /// Files and text shared to this app by other apps.
+    pub fn incoming_intents(&self) -> IncomingIntents<'_, R> {
+        IncomingIntents(self)
+    }
+
+    /// Custom URI scheme protocol that serves files to the webview.  
+    /// This requires the `protocol` feature.
+    #[cfg(feature = "protocol")]
+    pub fn webview_protocol(&self) -> WebviewProtocol<'_, R> {
+        WebviewProtocol(self)
+    }
+}
+
+
+/// Returns the name that does not conflict with ***taken***,  
+/// by appending a sequential number such as `name (1).ext`.
+fn unique_file_name(name: &str, taken: &std::collections::HashSet<String>) -> String {
+    if !taken.contains(name) {
+        return name.to_string()
+    }
+
+    let (stem, ext) = match name.rfind('.') {
+        Some(i) if 0 < i => (&name[..i], &name[i..]),
+        _ => (name, ""),
+    };
+
+    (1..)
+        .map(|i| format!("{stem} ({i}){ext}"))
+        .find(|n| !taken.contains(n))
+        .unwrap()
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PickerReq<'a> {
+    max_items: i32,
+    mime_types: &'a [String],
+    local_only: bool,
+    initial_location: Option<&'a FileUri>,
+    ordered: bool,
+}
+
+impl<'a> PickerReq<'a> {
+
+    fn new(options: &'a PickerOptions) -> Self {
+        Self {
+            max_items: options.max_items.clamp(1, i32::MAX as usize) as i32,
+            mime_types: &options.mime_types,
+            local_only: options.local_only,
+            initial_location: options.initial_location.as_ref(),
+            ordered: options.ordered,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShareReq<'a> {
+    uris: &'a [FileUri],
+    text: Option<&'a str>,
+    subject: Option<&'a str>,
+    title: Option<&'a str>,
+    mime_override: Option<&'a str>,
+}
+
+impl<'a> ShareReq<'a> {
+
+    fn new(options: &'a ShareOptions) -> Self {
+        Self {
+            uris: &options.uris,
+            text: options.text.as_deref(),
+            subject: options.subject.as_deref(),
+            title: options.title.as_deref(),
+            mime_override: options.mime_override.as_deref(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ViewReq<'a> {
+    uri: &'a FileUri,
+    always_show_chooser: bool,
+    chooser_title: Option<&'a str>,
+    target_package: Option<&'a str>,
+    grant_write: bool,
+    channel: Option<tauri::ipc::Channel>,
+}
+
+impl<'a> ViewReq<'a> {
+
+    fn new(uri: &'a FileUri, options: &'a ViewOptions, channel: Option<tauri::ipc::Channel>) -> Self {
+        Self {
+            uri,
+            always_show_chooser: options.always_show_chooser,
+            chooser_title: options.chooser_title.as_deref(),
+            target_package: options.target_package.as_deref(),
+            grant_write: options.grant_write,
+            channel,
+        }
+    }
+}
@@ -0,0 +1,587 @@
+use std::io::{Read, Seek, Write};
+use std::time::SystemTime;
+use crate::*;
+
+
+/// Minimum bytes between progress reports within an entry.
+const PROGRESS_INTERVAL_BYTES: u64 = 1024 * 1024;
+
+/// Creates an archive of the directory and writes it to ***dest_file***.
+#[cfg(target_os = "android")]
+pub(crate) fn zip_dir<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    src_dir: &FileUri,
+    dest_file: &FileUri,
+    options: &ArchiveOptions,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+) -> crate::Result<()> {
+
+    let entries = collect_entries(afs, src_dir)?;
+    let format = options.format.unwrap_or(ArchiveFormat::Zip);
+    let open = |uri: &FileUri| afs.open_file(uri, FileAccessMode::Read);
+
+    let total_bytes = entries.iter().map(|e| e.len).sum();
+    let mut progress = Progress::new(Some(entries.len() as u64), Some(total_bytes), on_progress);
+
+    if !afs.need_write_via_kotlin(dest_file)? {
+        let mut file = afs.open_file(dest_file, FileAccessMode::WriteTruncate)?;
+
+        // Zip needs random access, but some providers return a pipe.
+        if file.rewind().is_ok() {
+            return write_archive(&mut file, format, &entries, open, &mut progress)
+        }
+    }
+
+    let mut tmp = afs.private_storage().create_temp_file_for("zip_dir")?;
+    write_archive(tmp.as_file_mut(), format, &entries, open, &mut progress)?;
+    tmp.persist(dest_file)
+}
+
+/// Extracts the archive into ***dest_dir***, and returns the URIs of the created files.
+#[cfg(target_os = "android")]
+pub(crate) fn unzip<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    src: &FileUri,
+    dest_dir: &FileUri,
+    options: &ArchiveOptions,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+) -> crate::Result<Vec<FileUri>> {
+
+    // Zip and the detection of the format need random access.
+    let (file, _tmp) = open_seekable(afs, src, "unzip")?;
+
+    let mut uris = Vec::new();
+    let save = |path: &str, reader: &mut dyn Read| -> crate::Result<()> {
+        let uri = afs.create_file(dest_dir, path, None)?;
+
+        if afs.need_write_via_kotlin(&uri)? {
+            afs.write_via_kotlin_in(&uri, |file| std::io::copy(reader, file))?;
+        }
+        else {
+            let mut file = afs.open_file(&uri, FileAccessMode::WriteTruncate)?;
+            std::io::copy(reader, &mut file)?;
+        }
+
+        uris.push(uri);
+        Ok(())
+    };
+
+    extract_archive(file, options.format, save, &mut Progress::new(None, None, on_progress))?;
+    Ok(uris)
+}
+
+/// Opens the file in read-only mode with random access.  
+/// If the provider returns a pipe, the contents are copied to a temporary file,  
+/// which is removed when the returned [`TempFile`] is dropped.
+#[cfg(target_os = "android")]
+pub(crate) fn open_seekable<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    uri: &FileUri,
+    prefix: &str,
+) -> crate::Result<(std::fs::File, Option<TempFile<R>>)> {
+
+    let mut file = afs.open_file(uri, FileAccessMode::Read)?;
+
+    if file.rewind().is_ok() {
+        return Ok((file, None))
+    }
+
+    let mut tmp = afs.private_storage().create_temp_file_for(prefix)?;
+    std::io::copy(&mut file, tmp.as_file_mut())?;
+    tmp.as_file_mut().rewind()?;
+    Ok((tmp.as_file().try_clone()?, Some(tmp)))
+}
+
+/// Collects the descendants of the directory with the paths relative to it.
+#[cfg(target_os = "android")]
+fn collect_entries<R: tauri::Runtime>(afs: &AndroidFs<R>, dir: &FileUri) -> crate::Result<Vec<SourceEntry>> {
+    let mut entries = Vec::new();
+    let mut dirs = vec![(dir.clone(), String::new())];
+
+    while let Some((dir, prefix)) = dirs.pop() {
+        for entry in afs.read_dir(&dir)? {
+            match entry {
+                Entry::File { uri, name, last_modified, len, .. } => entries.push(SourceEntry {
+                    path: format!("{prefix}{name}"),
+                    uri: Some(uri),
+                    len,
+                    last_modified,
+                }),
+                Entry::Dir { uri, name, last_modified } => {
+                    let path = format!("{prefix}{name}");
+                    dirs.push((uri, format!("{path}/")));
+                    entries.push(SourceEntry { path, uri: None, len: 0, last_modified });
+                },
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+
+// Reading and writing archives below does not depend on Android, so it also works on Linux.
+
+/// File or directory to be archived.
+struct SourceEntry {
+
+    /// Path relative to the archived directory, separated by `/`.
+    path: String,
+
+    /// URI of the file, or None if this is a directory.
+    uri: Option<FileUri>,
+
+    len: u64,
+    last_modified: SystemTime,
+}
+
+fn write_archive<W: Write + Seek>(
+    output: W,
+    format: ArchiveFormat,
+    entries: &[SourceEntry],
+    open: impl Fn(&FileUri) -> crate::Result<std::fs::File>,
+    progress: &mut Progress<'_>,
+) -> crate::Result<()> {
+
+    match format {
+        ArchiveFormat::Zip => {
+            use zip::write::SimpleFileOptions;
+
+            let mut zip = zip::ZipWriter::new(output);
+            let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+            for entry in entries {
+                progress.start_entry(&entry.path);
+
+                match &entry.uri {
+                    Some(uri) => {
+                        let mut file = open(uri)?;
+                        zip.start_file(entry.path.as_str(), options.large_file(u32::MAX as u64 <= entry.len)).map_err(zip_error)?;
+                        std::io::copy(&mut ProgressReader { inner: &mut file, progress: &mut *progress }, &mut zip)?;
+                    },
+                    None => zip.add_directory(entry.path.as_str(), options).map_err(zip_error)?,
+                }
+
+                progress.finish_entry();
+            }
+
+            zip.finish().map_err(zip_error)?.flush()?;
+        },
+        ArchiveFormat::Tar => {
+            let mut tar = tar::Builder::new(output);
+            append_tar_entries(&mut tar, entries, open, progress)?;
+            tar.into_inner()?.flush()?;
+        },
+        ArchiveFormat::TarGz => {
+            let gz = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+            let mut tar = tar::Builder::new(gz);
+            append_tar_entries(&mut tar, entries, open, progress)?;
+            tar.into_inner()?.finish()?.flush()?;
+        },
+    }
+
+    Ok(())
+}
+
+fn append_tar_entries<W: Write>(
+    tar: &mut tar::Builder<W>,
+    entries: &[SourceEntry],
+    open: impl Fn(&FileUri) -> crate::Result<std::fs::File>,
+    progress: &mut Progress<'_>,
+) -> crate::Result<()> {
+
+    for entry in entries {
+        progress.start_entry(&entry.path);
+
+        let mtime = entry.last_modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(mtime);
+
+        match &entry.uri {
+            Some(uri) => {
+                let mut file = open(uri)?;
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_mode(0o644);
+                header.set_size(entry.len);
+                tar.append_data(&mut header, &entry.path, ProgressReader { inner: &mut file, progress: &mut *progress })?;
+            },
+            None => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o755);
+                header.set_size(0);
+                tar.append_data(&mut header, format!("{}/", entry.path), std::io::empty())?;
+            },
+        }
+
+        progress.finish_entry();
+    }
+
+    Ok(())
+}
+
+/// Extracts the files in the archive by passing them to ***save*** with the sanitized paths.  
+/// Directories, symbolic links and other special entries are skipped.
+fn extract_archive<I: Read + Seek>(
+    mut input: I,
+    format: Option<ArchiveFormat>,
+    mut save: impl FnMut(&str, &mut dyn Read) -> crate::Result<()>,
+    progress: &mut Progress<'_>,
+) -> crate::Result<()> {
+
+    let format = match format {
+        Some(format) => format,
+        None => detect_format(&mut input)?,
+    };
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(input).map_err(zip_error)?;
+
+            // Validate all paths before extracting anything, since the central directory is available.
+            let mut files = Vec::new();
+            let mut total_bytes = 0;
+            for i in 0..zip.len() {
+                let entry = zip.by_index_raw(i).map_err(zip_error)?;
+                let is_symlink = entry.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000);
+                if entry.is_dir() || is_symlink {
+                    continue
+                }
+
+                let path = sanitize_entry_path(entry.name()).ok_or_else(|| unsafe_path_error(entry.name()))?;
+                total_bytes += entry.size();
+                files.push((i, path));
+            }
+
+            progress.set_total(files.len() as u64, total_bytes);
+
+            for (i, path) in files {
+                let mut entry = zip.by_index(i).map_err(zip_error)?;
+                progress.start_entry(&path);
+                save(&path, &mut ProgressReader { inner: &mut entry, progress: &mut *progress })?;
+                progress.finish_entry();
+            }
+        },
+        ArchiveFormat::Tar => extract_tar(input, &mut save, progress)?,
+        ArchiveFormat::TarGz => extract_tar(flate2::read::MultiGzDecoder::new(input), &mut save, progress)?,
+    }
+
+    Ok(())
+}
+
+fn extract_tar(
+    input: impl Read,
+    save: &mut impl FnMut(&str, &mut dyn Read) -> crate::Result<()>,
+    progress: &mut Progress<'_>,
+) -> crate::Result<()> {
+
+    let mut tar = tar::Archive::new(input);
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+
+        match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous => {},
+            _ => continue,
+        }
+
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        let path = sanitize_entry_path(&name).ok_or_else(|| unsafe_path_error(&name))?;
+
+        progress.start_entry(&path);
+        save(&path, &mut ProgressReader { inner: &mut entry, progress: &mut *progress })?;
+        progress.finish_entry();
+    }
+
+    Ok(())
+}
+
+/// Detects the format from the first bytes, and rewinds the input.
+fn detect_format<I: Read + Seek>(input: &mut I) -> crate::Result<ArchiveFormat> {
+    let mut head = Vec::with_capacity(512);
+    input.by_ref().take(512).read_to_end(&mut head)?;
+    input.rewind()?;
+
+    if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+        return Ok(ArchiveFormat::Zip)
+    }
+    if head.starts_with(&[0x1f, 0x8b]) {
+        return Ok(ArchiveFormat::TarGz)
+    }
+    if head.get(257..262) == Some(&b"ustar"[..]) {
+        return Ok(ArchiveFormat::Tar)
+    }
+
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Unsupported archive format.").into())
+}
+
+/// Converts the path of the entry to a relative path that does not escape the destination directory.  
+/// Returns None if the path is absolute or contains `..`, which can be used for zip slip attacks.
+pub(crate) fn sanitize_entry_path(name: &str) -> Option<String> {
+    let name = name.replace('\\', "/");
+    if name.starts_with('/') || name.contains('\0') {
+        return None
+    }
+
+    let mut parts = Vec::new();
+
+    for part in name.split('/') {
+        match part {
+            "" | "." => continue,
+            ".." => return None,
+            // Drive letter such as `C:` and `C:a`
+            part if parts.is_empty() && is_drive_prefix(part) => return None,
+            part => parts.push(part),
+        }
+    }
+
+    match parts.is_empty() {
+        true => None,
+        false => Some(parts.join("/")),
+    }
+}
+
+fn is_drive_prefix(part: &str) -> bool {
+    let bytes = part.as_bytes();
+    part.ends_with(':') || (2 <= bytes.len() && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+fn unsafe_path_error(name: &str) -> crate::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("The archive contains an unsafe path: {name:?}")
+    ).into()
+}
+
+fn zip_error(e: zip::result::ZipError) -> crate::Error {
+    match e {
+        zip::result::ZipError::Io(e) => e.into(),
+        e => std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()).into(),
+    }
+}
+
+/// Tracks the progress and reports it to the callback.
+struct Progress<'a> {
+    current: ArchiveProgress,
+    on_progress: &'a mut dyn FnMut(&ArchiveProgress),
+    unreported_bytes: u64,
+}
+
+impl<'a> Progress<'a> {
+
+    fn new(
+        total_entries: Option<u64>,
+        total_bytes: Option<u64>,
+        on_progress: &'a mut dyn FnMut(&ArchiveProgress),
+    ) -> Self {
+
+        Self {
+            current: ArchiveProgress {
+                processed_entries: 0,
+                total_entries,
+                processed_bytes: 0,
+                total_bytes,
+                current_path: String::new(),
+            },
+            on_progress,
+            unreported_bytes: 0,
+        }
+    }
+
+    fn set_total(&mut self, total_entries: u64, total_bytes: u64) {
+        self.current.total_entries = Some(total_entries);
+        self.current.total_bytes = Some(total_bytes);
+    }
+
+    fn start_entry(&mut self, path: &str) {
+        self.current.current_path = path.to_string();
+        self.report();
+    }
+
+    fn add_bytes(&mut self, len: u64) {
+        self.current.processed_bytes += len;
+        self.unreported_bytes += len;
+
+        if PROGRESS_INTERVAL_BYTES <= self.unreported_bytes {
+            self.report();
+        }
+    }
+
+    fn finish_entry(&mut self) {
+        self.current.processed_entries += 1;
+        self.report();
+    }
+
+    fn report(&mut self) {
+        self.unreported_bytes = 0;
+        (self.on_progress)(&self.current);
+    }
+}
+
+/// Reader that adds the number of bytes read to the progress.
+struct ProgressReader<'p, 'a, I: Read> {
+    inner: I,
+    progress: &'p mut Progress<'a>,
+}
+
+impl<I: Read> Read for ProgressReader<'_, '_, I> {
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.progress.add_bytes(len as u64);
+        Ok(len)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+    use std::collections::BTreeMap;
+    use std::io::Cursor;
+
+    #[test]
+    fn sanitize_accepts_relative_paths() {
+        assert_eq!(sanitize_entry_path("a.txt").as_deref(), Some("a.txt"));
+        assert_eq!(sanitize_entry_path("./a//b/").as_deref(), Some("a/b"));
+        assert_eq!(sanitize_entry_path("a\\b").as_deref(), Some("a/b"));
+        assert_eq!(sanitize_entry_path("a/C:/b").as_deref(), Some("a/C:/b"));
+    }
+
+    #[test]
+    fn sanitize_rejects_unsafe_paths() {
+        let names = [
+            "", ".", "./", "..", "../a", "a/../b", "a/..", "..\\a", "a\\..\\b",
+            "/a", "/etc/passwd", "\\a", "C:", "C:/a", "C:\\a", "c:a", "a\0b",
+        ];
+
+        for name in names {
+            assert_eq!(sanitize_entry_path(name), None, "{name:?}");
+        }
+    }
+
+    #[test]
+    fn detects_format_and_rewinds() {
+        let dir = TestDir::new("archive-detect");
+        let entries = source_entries(&dir);
+
+        for format in [ArchiveFormat::Zip, ArchiveFormat::Tar, ArchiveFormat::TarGz] {
+            let mut input = Cursor::new(archive(format, &entries));
+
+            assert_eq!(detect_format(&mut input).unwrap(), format);
+            assert_eq!(input.position(), 0);
+        }
+
+        assert!(detect_format(&mut Cursor::new(b"plain text".to_vec())).is_err());
+        assert!(detect_format(&mut Cursor::new(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = TestDir::new("archive-round-trip");
+        let entries = source_entries(&dir);
+
+        for format in [ArchiveFormat::Zip, ArchiveFormat::Tar, ArchiveFormat::TarGz] {
+            let bytes = archive(format, &entries);
+
+            for specified in [None, Some(format)] {
+                let mut reports = Vec::new();
+                let files = extract(&bytes, specified, &mut reports).unwrap();
+
+                assert_eq!(files, BTreeMap::from([
+                    ("a.txt".to_string(), b"a".to_vec()),
+                    ("sub/b.txt".to_string(), vec![7; 3 * PROGRESS_INTERVAL_BYTES as usize]),
+                    ("sub/empty.txt".to_string(), Vec::new()),
+                ]), "{format:?}");
+
+                let last = reports.last().unwrap();
+                assert_eq!(last.processed_entries, 3);
+                assert_eq!(last.processed_bytes, 1 + 3 * PROGRESS_INTERVAL_BYTES);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_zip_slip() {
+        use zip::write::SimpleFileOptions;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("a.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"a").unwrap();
+        zip.start_file("../evil.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"evil").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let mut saved = Vec::new();
+        let mut on_progress = |_: &ArchiveProgress| {};
+        let save = |path: &str, _: &mut dyn Read| -> crate::Result<()> {
+            saved.push(path.to_string());
+            Ok(())
+        };
+        let result = extract_archive(Cursor::new(bytes), None, save, &mut Progress::new(None, None, &mut on_progress));
+
+        // Nothing is extracted, since all paths are validated first.
+        assert!(result.is_err());
+        assert!(saved.is_empty());
+    }
+
+    /// Creates files in ***dir*** and returns the entries to archive them.
+    fn source_entries(dir: &TestDir) -> Vec<SourceEntry> {
+        let files: [(&str, Vec<u8>); 3] = [
+            ("a.txt", b"a".to_vec()),
+            ("sub/b.txt", vec![7; 3 * PROGRESS_INTERVAL_BYTES as usize]),
+            ("sub/empty.txt", Vec::new()),
+        ];
+
+        let mut entries = vec![SourceEntry { path: "sub".into(), uri: None, len: 0, last_modified: SystemTime::now() }];
+
+        for (path, contents) in files {
+            let file_path = dir.join(path);
+            std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            std::fs::write(&file_path, &contents).unwrap();
+
+            entries.push(SourceEntry {
+                path: path.into(),
+                uri: Some(FileUri::from(file_path)),
+                len: contents.len() as u64,
+                last_modified: SystemTime::now(),
+            });
+        }
+
+        entries
+    }
+
+    fn archive(format: ArchiveFormat, entries: &[SourceEntry]) -> Vec<u8> {
+        let open = |uri: &FileUri| -> crate::Result<std::fs::File> {
+            Ok(std::fs::File::open(uri.uri.strip_prefix("file://").unwrap())?)
+        };
+
+        let mut on_progress = |_: &ArchiveProgress| {};
+        let mut output = Cursor::new(Vec::new());
+        write_archive(&mut output, format, entries, open, &mut Progress::new(None, None, &mut on_progress)).unwrap();
+        output.into_inner()
+    }
+
+    fn extract(
+        bytes: &[u8],
+        format: Option<ArchiveFormat>,
+        reports: &mut Vec<ArchiveProgress>,
+    ) -> crate::Result<BTreeMap<String, Vec<u8>>> {
+
+        let mut files = BTreeMap::new();
+        let save = |path: &str, reader: &mut dyn Read| -> crate::Result<()> {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            files.insert(path.to_string(), buf);
+            Ok(())
+        };
+
+        let mut on_progress = |p: &ArchiveProgress| reports.push(p.clone());
+        extract_archive(Cursor::new(bytes), format, save, &mut Progress::new(None, None, &mut on_progress))?;
+        Ok(files)
+    }
+}
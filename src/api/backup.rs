@@ -0,0 +1,636 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use sha2::Digest as _;
+use super::archive::sanitize_entry_path;
+use crate::*;
+
+
+/// Version of the backup format written by this plugin.
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const FILES_PREFIX: &str = "files/";
+
+/// Directory under the restored directory, in which the files are restored first by [`ImportConflictPolicy::ReplaceAll`].  
+/// This starts with the prefix of the internal entries, so it is neither backed up nor removed.
+const STAGING_DIR_NAME: &str = "pluginAndroidFs-importStaging";
+
+/// Exports the files in ***dir*** to a new backup file in ***dest_dir***, and returns its URI.
+#[cfg(target_os = "android")]
+pub(crate) fn export_private_data<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    dir: PrivateDir,
+    dest_dir: &FileUri,
+    filter: &mut dyn FnMut(&str) -> bool,
+) -> crate::Result<FileUri> {
+
+    use tauri::Manager as _;
+
+    let base = afs.private_storage().resolve_path(dir)?;
+    let files = collect_files(&base, filter);
+
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        app_identifier: afs.app.config().identifier.clone(),
+        app_version: afs.app.package_info().version.to_string(),
+        created_at,
+        files: Vec::new(),
+    };
+
+    let dest = afs.create_file(
+        dest_dir,
+        format!("{}-backup-{}.tar.gz", manifest.app_identifier, created_at / 1000),
+        Some("application/gzip")
+    )?;
+
+    let result = match afs.need_write_via_kotlin(&dest)? {
+        true => afs.private_storage()
+            .create_temp_file_for("export_private_data")
+            .and_then(|mut tmp| {
+                write_backup(tmp.as_file_mut(), &files, manifest)?;
+                tmp.persist(&dest)
+            }),
+        false => afs.open_file(&dest, FileAccessMode::WriteTruncate)
+            .and_then(|mut file| write_backup(&mut file, &files, manifest)),
+    };
+
+    if let Err(e) = result {
+        let _ = afs.remove_file(&dest);
+        return Err(e)
+    }
+
+    Ok(dest)
+}
+
+/// Validates the backup file and restores the files in it to ***dir***.
+#[cfg(target_os = "android")]
+pub(crate) fn import_private_data<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    src: &FileUri,
+    dir: PrivateDir,
+    conflict_policy: ImportConflictPolicy,
+) -> crate::Result<PrivateDataImportResult> {
+
+    use tauri::Manager as _;
+
+    // The backup is read twice, first to validate and then to restore.
+    let (mut file, _tmp) = super::archive::open_seekable(afs, src, "import_private_data")?;
+
+    let identifier = &afs.app.config().identifier;
+    let version = &afs.app.package_info().version;
+    let manifest = verify_backup(&mut file, identifier, version)?;
+
+    file.rewind()?;
+
+    let base = afs.private_storage().resolve_path(dir)?;
+    restore_backup(file, &manifest, &base, conflict_policy)
+}
+
+
+// Reading and writing backups below does not depend on Android, so it also works on Linux.
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    format_version: u32,
+    app_identifier: String,
+    app_version: String,
+
+    /// Milliseconds since the UNIX epoch.
+    created_at: u64,
+
+    files: Vec<ManifestFile>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestFile {
+    path: String,
+    size: u64,
+    sha256: String,
+}
+
+/// Collects the files in the directory with the paths relative to it, excluding those used internally by this plugin.  
+/// Values of [`Store`] are included, since they are the data of the app.
+fn collect_files(base: &Path, filter: &mut dyn FnMut(&str) -> bool) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    let mut dirs = vec![(base.to_path_buf(), String::new())];
+
+    while let Some((dir, prefix)) = dirs.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue
+        };
+
+        for entry in read_dir.flatten() {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue
+            };
+
+            if prefix.is_empty() && is_internal(&name) {
+                continue
+            }
+
+            // Symbolic links are not followed.
+            let Ok(file_type) = entry.file_type() else {
+                continue
+            };
+
+            let path = format!("{prefix}{name}");
+
+            if file_type.is_dir() {
+                dirs.push((entry.path(), format!("{path}/")));
+            }
+            else if file_type.is_file() && filter(&path) {
+                files.push((path, entry.path()));
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+fn is_internal(top_level_name: &str) -> bool {
+    top_level_name.starts_with("pluginAndroidFs-") && top_level_name != STORE_DIR_RELATIVE_PATH
+}
+
+/// Writes the files and then the manifest with their hashes as tar.gz.
+fn write_backup<W: Write>(output: W, files: &[(String, PathBuf)], mut manifest: Manifest) -> crate::Result<()> {
+    let gz = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+    let mut tar = tar::Builder::new(gz);
+
+    for (path, abs_path) in files {
+        let mut file = match std::fs::File::open(abs_path) {
+            // Removed after being collected
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            result => result?,
+        };
+
+        let metadata = file.metadata()?;
+        let mtime = metadata.modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_size(metadata.len());
+
+        let mut reader = HashingReader::new((&mut file).take(metadata.len()));
+        tar.append_data(&mut header, format!("{FILES_PREFIX}{path}"), &mut reader)?;
+
+        let (size, sha256) = reader.finish();
+        manifest.files.push(ManifestFile { path: path.clone(), size, sha256 });
+    }
+
+    let json = serde_json::to_vec_pretty(&manifest)?;
+
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_mode(0o644);
+    header.set_size(json.len() as u64);
+    tar.append_data(&mut header, MANIFEST_NAME, json.as_slice())?;
+
+    tar.into_inner()?.finish()?.flush()?;
+    Ok(())
+}
+
+/// Reads the entire backup, and checks that the manifest is compatible with this app and the files match it.
+fn verify_backup(
+    input: impl Read,
+    identifier: &str,
+    version: &semver::Version,
+) -> crate::Result<Manifest> {
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(input));
+    let mut manifest = None;
+    let mut actual = HashMap::new();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            continue
+        }
+
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+
+        if name == MANIFEST_NAME {
+            let parsed: Manifest = serde_json::from_reader(&mut entry)
+                .map_err(|e| Error::InvalidBackup(format!("The manifest is broken: {e}")))?;
+
+            manifest = Some(parsed);
+        }
+        else if let Some(path) = name.strip_prefix(FILES_PREFIX) {
+            let path = sanitize_entry_path(path)
+                .ok_or_else(|| Error::InvalidBackup(format!("The backup contains an unsafe path: {name:?}")))?;
+
+            let mut reader = HashingReader::new(&mut entry);
+            std::io::copy(&mut reader, &mut std::io::sink())?;
+            actual.insert(path, reader.finish());
+        }
+        else {
+            return Err(Error::InvalidBackup(format!("The backup contains an unknown entry: {name:?}")))
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| Error::InvalidBackup("The manifest is missing.".into()))?;
+    check_compatibility(&manifest, identifier, version)?;
+
+    let expected = manifest.files.iter()
+        .map(|f| (f.path.clone(), (f.size, f.sha256.clone())))
+        .collect::<HashMap<_, _>>();
+
+    if expected.len() != manifest.files.len() || expected != actual {
+        return Err(Error::InvalidBackup("The files do not match the manifest. The backup may be corrupted.".into()))
+    }
+
+    Ok(manifest)
+}
+
+fn check_compatibility(manifest: &Manifest, identifier: &str, version: &semver::Version) -> crate::Result<()> {
+    if FORMAT_VERSION < manifest.format_version {
+        return Err(Error::IncompatibleBackup(format!(
+            "The backup format version {} is not supported.", manifest.format_version
+        )))
+    }
+
+    if manifest.app_identifier != identifier {
+        return Err(Error::IncompatibleBackup(format!(
+            "The backup was created by another app {:?}.", manifest.app_identifier
+        )))
+    }
+
+    let backup_version = semver::Version::parse(&manifest.app_version)
+        .map_err(|_| Error::InvalidBackup(format!("Invalid app version: {:?}", manifest.app_version)))?;
+
+    if version < &backup_version {
+        return Err(Error::IncompatibleBackup(format!(
+            "The backup was created by the newer version {backup_version} of the app, but the current version is {version}."
+        )))
+    }
+
+    Ok(())
+}
+
+/// Restores the files in the verified backup to ***base***.
+fn restore_backup(
+    input: impl Read,
+    manifest: &Manifest,
+    base: &Path,
+    conflict_policy: ImportConflictPolicy,
+) -> crate::Result<PrivateDataImportResult> {
+
+    match conflict_policy {
+        ImportConflictPolicy::Fail => {
+            if let Some(file) = manifest.files.iter().find(|f| base.join(&f.path).exists()) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("{:?} already exists.", file.path)
+                ).into())
+            }
+        },
+        ImportConflictPolicy::ReplaceAll => {
+            // Restore into the staging directory first, so that the existing files are kept if this fails in the middle.
+            let staging = base.join(STAGING_DIR_NAME);
+            let _ = std::fs::remove_dir_all(&staging);
+            std::fs::create_dir_all(&staging)?;
+
+            let result = restore_entries(input, manifest, &staging, conflict_policy)
+                .and_then(|result| swap_in(&staging, base).map(|_| result));
+
+            let _ = std::fs::remove_dir_all(&staging);
+            return result
+        },
+        ImportConflictPolicy::Overwrite | ImportConflictPolicy::Skip => {},
+    }
+
+    restore_entries(input, manifest, base, conflict_policy)
+}
+
+fn restore_entries(
+    input: impl Read,
+    manifest: &Manifest,
+    base: &Path,
+    conflict_policy: ImportConflictPolicy,
+) -> crate::Result<PrivateDataImportResult> {
+
+    let mut result = PrivateDataImportResult { restored_files: 0, skipped_files: 0 };
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(input));
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            continue
+        }
+
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+
+        let Some(path) = name.strip_prefix(FILES_PREFIX).and_then(sanitize_entry_path) else {
+            continue
+        };
+        let Some(expected) = manifest.files.iter().find(|f| f.path == path) else {
+            continue
+        };
+
+        let dest = base.join(&path);
+
+        if conflict_policy == ImportConflictPolicy::Skip && dest.exists() {
+            result.skipped_files += 1;
+            continue
+        }
+
+        restore_file(&mut entry, &dest, expected)?;
+        result.restored_files += 1;
+    }
+
+    Ok(result)
+}
+
+/// Replaces the entries in ***base*** with those in ***staging***, except those used internally by this plugin.
+fn swap_in(staging: &Path, base: &Path) -> crate::Result<()> {
+    remove_all(base)?;
+
+    for entry in std::fs::read_dir(staging)? {
+        let entry = entry?;
+        std::fs::rename(entry.path(), base.join(entry.file_name()))?;
+    }
+
+    Ok(())
+}
+
+/// Writes the file to a temporary file and renames it after checking the hash again,  
+/// since the source may have been changed after it was verified.
+fn restore_file(reader: &mut impl Read, dest: &Path, expected: &ManifestFile) -> crate::Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut tmp_name = dest.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".pluginAndroidFs-import.tmp");
+    let tmp_path = dest.with_file_name(tmp_name);
+
+    let result = (|| -> crate::Result<()> {
+        let mut reader = HashingReader::new(reader);
+        let mut file = std::fs::File::create(&tmp_path)?;
+        std::io::copy(&mut reader, &mut file)?;
+        file.sync_all()?;
+
+        if reader.finish() != (expected.size, expected.sha256.clone()) {
+            return Err(Error::InvalidBackup("The backup was changed while restoring.".into()))
+        }
+
+        std::fs::rename(&tmp_path, dest)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+
+    result
+}
+
+/// Removes the entries in the directory, except those used internally by this plugin.
+fn remove_all(base: &Path) -> crate::Result<()> {
+    let Ok(read_dir) = std::fs::read_dir(base) else {
+        return Ok(())
+    };
+
+    for entry in read_dir.flatten() {
+        if entry.file_name().to_str().is_some_and(is_internal) {
+            continue
+        }
+
+        match entry.file_type()?.is_dir() {
+            true => std::fs::remove_dir_all(entry.path())?,
+            false => std::fs::remove_file(entry.path())?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Reader that computes the size and SHA-256 of the contents read.
+struct HashingReader<I: Read> {
+    inner: I,
+    hasher: sha2::Sha256,
+    len: u64,
+}
+
+impl<I: Read> HashingReader<I> {
+
+    fn new(inner: I) -> Self {
+        Self { inner, hasher: sha2::Sha256::new(), len: 0 }
+    }
+
+    /// Returns the size and SHA-256 in lowercase hex.
+    fn finish(self) -> (u64, String) {
+        let hash = self.hasher.finalize().iter().map(|b| format!("{b:02x}")).collect();
+        (self.len, hash)
+    }
+}
+
+impl<I: Read> Read for HashingReader<I> {
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.hasher.update(&buf[..len]);
+        self.len += len as u64;
+        Ok(len)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+
+    const IDENTIFIER: &str = "com.example.app";
+
+    fn version(v: &str) -> semver::Version {
+        semver::Version::parse(v).unwrap()
+    }
+
+    fn manifest(files: Vec<ManifestFile>) -> Manifest {
+        Manifest {
+            format_version: FORMAT_VERSION,
+            app_identifier: IDENTIFIER.into(),
+            app_version: "1.2.0".into(),
+            created_at: 0,
+            files,
+        }
+    }
+
+    /// Creates the files in a directory and exports them.
+    fn backup(files: &[(&str, &str)]) -> Vec<u8> {
+        let src = TestDir::new("backup-src");
+        for (path, contents) in files {
+            let path = src.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        let mut bytes = Vec::new();
+        write_backup(&mut bytes, &collect_files(src.path(), &mut |_| true), manifest(Vec::new())).unwrap();
+        bytes
+    }
+
+    /// Creates a backup with the entries as is, without validating the names.
+    fn raw_backup(entries: Vec<(&str, Vec<u8>)>) -> Vec<u8> {
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+
+        for (name, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(0o644);
+            header.set_size(contents.len() as u64);
+            header.set_cksum();
+            tar.append(&header, contents.as_slice()).unwrap();
+        }
+
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn hashed(path: &str, contents: &[u8]) -> ManifestFile {
+        let (size, sha256) = {
+            let mut reader = HashingReader::new(contents);
+            std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+            reader.finish()
+        };
+        ManifestFile { path: path.into(), size, sha256 }
+    }
+
+    fn verify(bytes: &[u8]) -> crate::Result<Manifest> {
+        verify_backup(bytes, IDENTIFIER, &version("1.2.0"))
+    }
+
+    #[test]
+    fn verifies_exported_backup() {
+        let store = format!("{STORE_DIR_RELATIVE_PATH}/settings.json");
+        let bytes = backup(&[("a.txt", "a"), ("sub/b.txt", "b"), ("pluginAndroidFs-internal/a", "x"), (store.as_str(), "{}")]);
+
+        let manifest = verify(&bytes).unwrap();
+        let paths = manifest.files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, vec!["a.txt", store.as_str(), "sub/b.txt"]);
+    }
+
+    #[test]
+    fn rejects_files_not_matching_manifest() {
+        let json = |files: Vec<ManifestFile>| serde_json::to_vec(&manifest(files)).unwrap();
+        let a = || b"a".to_vec();
+
+        let cases = [
+            // Changed contents
+            raw_backup(vec![("files/a.txt", b"changed".to_vec()), (MANIFEST_NAME, json(vec![hashed("a.txt", b"a")]))]),
+            // Missing file
+            raw_backup(vec![(MANIFEST_NAME, json(vec![hashed("a.txt", b"a")]))]),
+            // File not in the manifest
+            raw_backup(vec![("files/a.txt", a()), (MANIFEST_NAME, json(Vec::new()))]),
+            // Unknown entry
+            raw_backup(vec![("other.txt", a()), (MANIFEST_NAME, json(Vec::new()))]),
+            // Unsafe path
+            raw_backup(vec![("files/../a.txt", a()), (MANIFEST_NAME, json(vec![hashed("../a.txt", b"a")]))]),
+            // Missing manifest
+            raw_backup(vec![("files/a.txt", a())]),
+            // Broken manifest
+            raw_backup(vec![(MANIFEST_NAME, b"{".to_vec())]),
+        ];
+
+        // The valid one for comparison
+        assert!(verify(&raw_backup(vec![("files/a.txt", a()), (MANIFEST_NAME, json(vec![hashed("a.txt", b"a")]))])).is_ok());
+
+        for (i, bytes) in cases.iter().enumerate() {
+            assert!(matches!(verify(bytes), Err(Error::InvalidBackup(_))), "case {i}");
+        }
+    }
+
+    #[test]
+    fn checks_compatibility() {
+        let check = |f: fn(&mut Manifest)| {
+            let mut manifest = manifest(Vec::new());
+            f(&mut manifest);
+            check_compatibility(&manifest, IDENTIFIER, &version("1.2.0"))
+        };
+
+        assert!(check(|_| {}).is_ok());
+        assert!(check(|m| m.app_version = "1.1.9".into()).is_ok());
+        assert!(matches!(check(|m| m.app_version = "1.3.0".into()), Err(Error::IncompatibleBackup(_))));
+        assert!(matches!(check(|m| m.app_identifier = "com.example.other".into()), Err(Error::IncompatibleBackup(_))));
+        assert!(matches!(check(|m| m.format_version = FORMAT_VERSION + 1), Err(Error::IncompatibleBackup(_))));
+        assert!(matches!(check(|m| m.app_version = "latest".into()), Err(Error::InvalidBackup(_))));
+    }
+
+    /// Creates a directory with existing files, restores the backup to it, and returns the directory.
+    fn restore(bytes: &[u8], manifest: &Manifest, policy: ImportConflictPolicy) -> (TestDir, crate::Result<PrivateDataImportResult>) {
+        let base = TestDir::new("backup-dest");
+        std::fs::write(base.join("a.txt"), b"old").unwrap();
+        std::fs::write(base.join("keep.txt"), b"keep").unwrap();
+        std::fs::create_dir_all(base.join("pluginAndroidFs-internal")).unwrap();
+
+        let result = restore_backup(bytes, manifest, base.path(), policy);
+        (base, result)
+    }
+
+    fn read(base: &TestDir, path: &str) -> Option<Vec<u8>> {
+        std::fs::read(base.join(path)).ok()
+    }
+
+    #[test]
+    fn restores_with_each_policy() {
+        let bytes = backup(&[("a.txt", "new"), ("sub/b.txt", "b")]);
+        let manifest = verify(&bytes).unwrap();
+
+        let (base, result) = restore(&bytes, &manifest, ImportConflictPolicy::Overwrite);
+        assert_eq!(result.unwrap(), PrivateDataImportResult { restored_files: 2, skipped_files: 0 });
+        assert_eq!(read(&base, "a.txt").unwrap(), b"new");
+        assert_eq!(read(&base, "sub/b.txt").unwrap(), b"b");
+        assert_eq!(read(&base, "keep.txt").unwrap(), b"keep");
+
+        let (base, result) = restore(&bytes, &manifest, ImportConflictPolicy::Skip);
+        assert_eq!(result.unwrap(), PrivateDataImportResult { restored_files: 1, skipped_files: 1 });
+        assert_eq!(read(&base, "a.txt").unwrap(), b"old");
+        assert_eq!(read(&base, "sub/b.txt").unwrap(), b"b");
+
+        let (base, result) = restore(&bytes, &manifest, ImportConflictPolicy::Fail);
+        assert!(result.is_err());
+        assert_eq!(read(&base, "a.txt").unwrap(), b"old");
+        assert_eq!(read(&base, "sub/b.txt"), None);
+
+        let (base, result) = restore(&bytes, &manifest, ImportConflictPolicy::ReplaceAll);
+        assert_eq!(result.unwrap(), PrivateDataImportResult { restored_files: 2, skipped_files: 0 });
+        assert_eq!(read(&base, "a.txt").unwrap(), b"new");
+        assert_eq!(read(&base, "sub/b.txt").unwrap(), b"b");
+        assert_eq!(read(&base, "keep.txt"), None);
+        assert!(base.join("pluginAndroidFs-internal").exists());
+        assert!(!base.join(STAGING_DIR_NAME).exists());
+    }
+
+    #[test]
+    fn replace_all_keeps_existing_files_on_failure() {
+        let bytes = backup(&[("a.txt", "new"), ("sub/b.txt", "b")]);
+        let mut manifest = verify(&bytes).unwrap();
+
+        // Same as the backup being changed after it was verified
+        manifest.files[1].sha256 = hashed("sub/b.txt", b"c").sha256;
+
+        let (base, result) = restore(&bytes, &manifest, ImportConflictPolicy::ReplaceAll);
+        assert!(matches!(result, Err(Error::InvalidBackup(_))));
+        assert_eq!(read(&base, "a.txt").unwrap(), b"old");
+        assert_eq!(read(&base, "keep.txt").unwrap(), b"keep");
+        assert_eq!(read(&base, "sub/b.txt"), None);
+        assert!(!base.join(STAGING_DIR_NAME).exists());
+    }
+}
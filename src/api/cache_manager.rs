@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::*;
+
+
+/// API of managing the size of the files in [`PrivateDir::Cache`].
+/// 
+/// The system may remove cache files when the device is low on storage,  
+/// but this app can also cap its own usage by [`CacheManager::set_limit`].  
+/// The files are removed in least recently used order,  
+/// based on the later of the last modified time and the last accessed time.  
+/// Since many devices do not update the last accessed time on read,  
+/// please call [`CacheManager::touch`] when the file is used.
+/// 
+/// The files used internally by this plugin, such as [`TempFile`] and [`ThumbnailCache`], are not included.
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::{AndroidFsExt, CacheLimit};
+///
+///     let api = app.android_fs();
+///     let cache_manager = api.private_storage().cache_manager();
+///
+///     cache_manager.set_limit(CacheLimit {
+///         max_bytes: Some(256 * 1024 * 1024),
+///         max_age: Some(std::time::Duration::from_secs(7 * 24 * 60 * 60)),
+///     }).unwrap();
+/// }
+/// ```
+pub struct CacheManager<'a, R: tauri::Runtime>(pub(crate) &'a AndroidFs<R>);
+
+#[derive(Default)]
+pub(crate) struct CacheManagerState {
+    limit: CacheLimit,
+}
+
+impl<'a, R: tauri::Runtime> CacheManager<'a, R> {
+
+    /// Sets the limits of the cache files, and removes the files that exceed them immediately.
+    /// 
+    /// This is not persisted, so please set it each time the app starts.  
+    /// The limits are not applied automatically when files are written,  
+    /// so please call [`CacheManager::trim`] after writing large files or periodically.  
+    /// By default, there are no limits.
+    /// 
+    /// # Support
+    /// All.
+    pub fn set_limit(&self, limit: CacheLimit) -> crate::Result<CacheTrimResult> {
+        on_android!({
+            self.lock_state().limit = limit;
+            self.trim()
+        })
+    }
+
+    /// Gets the limits of the cache files.
+    /// 
+    /// # Support
+    /// All.
+    pub fn limit(&self) -> crate::Result<CacheLimit> {
+        on_android!({
+            Ok(self.lock_state().limit)
+        })
+    }
+
+    /// Removes the least recently used files until the limits set by [`CacheManager::set_limit`] are satisfied.
+    /// 
+    /// # Support
+    /// All.
+    pub fn trim(&self) -> crate::Result<CacheTrimResult> {
+        on_android!({
+            let dir = self.0.private_storage().resolve_path(PrivateDir::Cache)?;
+            let limit = self.lock_state().limit;
+            Ok(evict(&dir, &limit, SystemTime::now()))
+        })
+    }
+
+    /// Gets the total bytes of the cache files.
+    /// 
+    /// # Support
+    /// All.
+    pub fn total_bytes(&self) -> crate::Result<u64> {
+        on_android!({
+            let dir = self.0.private_storage().resolve_path(PrivateDir::Cache)?;
+            Ok(collect_entries(&dir).iter().map(|e| e.len).sum())
+        })
+    }
+
+    /// Marks the file as used now, so that it is removed later than others.
+    /// 
+    /// # Args
+    /// - ***relative_path*** :  
+    /// Relative path of the file from [`PrivateDir::Cache`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn touch(&self, relative_path: impl AsRef<str>) -> crate::Result<()> {
+        on_android!({
+            let path = self.0.private_storage().resolve_path_with(PrivateDir::Cache, relative_path)?;
+            let file = std::fs::File::open(path)?;
+            file.set_times(std::fs::FileTimes::new().set_accessed(SystemTime::now()))?;
+            Ok(())
+        })
+    }
+
+    /// Gets the cache quota of this app determined by the system.  
+    /// When the device is low on storage, the cache of apps exceeding this are removed first.
+    /// 
+    /// This value changes over time depending on how frequently the user uses this app.
+    /// 
+    /// # Support
+    /// All.
+    /// 
+    /// # References
+    /// <https://developer.android.com/reference/android/os/storage/StorageManager#getCacheQuotaBytes(java.util.UUID)>
+    pub fn quota_bytes(&self) -> crate::Result<u64> {
+        on_android!({
+            impl_de!(struct Res { value: u64 });
+
+            self.0.api
+                .run_mobile_plugin::<Res>("getCacheQuotaBytes", "")
+                .map(|v| v.value)
+                .map_err(Into::into)
+        })
+    }
+
+
+    #[cfg(target_os = "android")]
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, CacheManagerState> {
+        self.0.cache_manager_state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+/// Prefix of the files and directories used internally by this plugin.
+const INTERNAL_ENTRY_PREFIX: &str = "pluginAndroidFs-";
+
+struct CacheEntry {
+    path: PathBuf,
+    len: u64,
+    last_used: SystemTime,
+}
+
+/// Removes the expired files and then the least recently used files until the limit is satisfied.
+fn evict(dir: &Path, limit: &CacheLimit, now: SystemTime) -> CacheTrimResult {
+    let mut entries = collect_entries(dir);
+
+    // Newest first
+    entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+
+    let mut result = CacheTrimResult { removed_files: 0, removed_bytes: 0, remaining_bytes: 0 };
+    let mut exceeded = false;
+
+    for entry in entries {
+        let expired = limit.max_age
+            .is_some_and(|max_age| now.duration_since(entry.last_used).is_ok_and(|age| max_age < age));
+
+        // Once the limit is exceeded, all older files are removed even if they are small enough to fit.
+        exceeded = exceeded || limit.max_bytes
+            .is_some_and(|max_bytes| max_bytes < result.remaining_bytes + entry.len);
+
+        if (expired || exceeded) && std::fs::remove_file(&entry.path).is_ok() {
+            result.removed_files += 1;
+            result.removed_bytes += entry.len;
+        }
+        else {
+            result.remaining_bytes += entry.len;
+        }
+    }
+
+    if 0 < result.removed_files {
+        remove_empty_dirs(dir, true);
+    }
+
+    result
+}
+
+/// Collects the files in the directory recursively, excluding those used internally by this plugin.
+fn collect_entries(dir: &Path) -> Vec<CacheEntry> {
+    let mut entries = Vec::new();
+    let mut dirs = vec![(dir.to_path_buf(), true)];
+
+    while let Some((dir, is_root)) = dirs.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue
+        };
+
+        for entry in read_dir.flatten() {
+            if is_root && entry.file_name().to_string_lossy().starts_with(INTERNAL_ENTRY_PREFIX) {
+                continue
+            }
+
+            let Ok(metadata) = entry.metadata() else {
+                continue
+            };
+
+            if metadata.is_dir() {
+                dirs.push((entry.path(), false));
+            }
+            else if metadata.is_file() {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                let accessed = metadata.accessed().unwrap_or(SystemTime::UNIX_EPOCH);
+
+                entries.push(CacheEntry {
+                    path: entry.path(),
+                    len: metadata.len(),
+                    last_used: modified.max(accessed),
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+/// Removes the empty directories left by eviction, and returns whether the directory is empty.  
+/// The root directory itself is not removed.
+fn remove_empty_dirs(dir: &Path, is_root: bool) -> bool {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return false
+    };
+
+    let mut is_empty = true;
+
+    for entry in read_dir.flatten() {
+        let name = entry.file_name();
+        let is_internal = is_root && name.to_string_lossy().starts_with(INTERNAL_ENTRY_PREFIX);
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+
+        if is_internal || !is_dir || !remove_empty_dirs(&entry.path(), false) {
+            is_empty = false;
+        }
+    }
+
+    if is_empty && !is_root {
+        return std::fs::remove_dir(dir).is_ok()
+    }
+
+    is_empty
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+    use std::time::Duration;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000)
+    }
+
+    /// Creates a file of ***len*** bytes used ***age*** ago.
+    fn create(dir: &TestDir, name: &str, len: usize, age: Duration) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, vec![0; len]).unwrap();
+
+        let time = now() - age;
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_times(std::fs::FileTimes::new().set_accessed(time).set_modified(time)).unwrap();
+    }
+
+    #[test]
+    fn removes_least_recently_used_first() {
+        let dir = TestDir::new("cache-bytes");
+        create(&dir, "new", 10, HOUR);
+        create(&dir, "mid", 10, 2 * HOUR);
+        create(&dir, "sub/old", 10, 3 * HOUR);
+
+        let limit = CacheLimit { max_bytes: Some(25), max_age: None };
+        let result = evict(dir.path(), &limit, now());
+
+        assert_eq!((result.removed_files, result.removed_bytes, result.remaining_bytes), (1, 10, 20));
+        assert!(dir.join("new").exists());
+        assert!(dir.join("mid").exists());
+        assert!(!dir.join("sub").exists());
+    }
+
+    #[test]
+    fn removes_all_older_files_once_exceeded() {
+        let dir = TestDir::new("cache-older");
+        create(&dir, "new", 10, HOUR);
+        create(&dir, "big", 100, 2 * HOUR);
+        create(&dir, "small", 1, 3 * HOUR);
+
+        let limit = CacheLimit { max_bytes: Some(50), max_age: None };
+        let result = evict(dir.path(), &limit, now());
+
+        assert_eq!((result.removed_files, result.remaining_bytes), (2, 10));
+        assert!(dir.join("new").exists());
+        assert!(!dir.join("big").exists());
+        assert!(!dir.join("small").exists());
+    }
+
+    #[test]
+    fn removes_expired_files() {
+        let dir = TestDir::new("cache-age");
+        create(&dir, "new", 10, HOUR);
+        create(&dir, "old", 10, 3 * HOUR);
+
+        let limit = CacheLimit { max_bytes: None, max_age: Some(2 * HOUR) };
+        let result = evict(dir.path(), &limit, now());
+
+        assert_eq!((result.removed_files, result.remaining_bytes), (1, 10));
+        assert!(dir.join("new").exists());
+        assert!(!dir.join("old").exists());
+    }
+
+    #[test]
+    fn skips_internal_entries() {
+        let dir = TestDir::new("cache-internal");
+        create(&dir, "old", 10, 3 * HOUR);
+        create(&dir, &format!("{INTERNAL_ENTRY_PREFIX}file"), 10, 3 * HOUR);
+        create(&dir, &format!("{INTERNAL_ENTRY_PREFIX}dir/a"), 10, 3 * HOUR);
+
+        let limit = CacheLimit { max_bytes: Some(0), max_age: Some(HOUR) };
+        let result = evict(dir.path(), &limit, now());
+
+        assert_eq!((result.removed_files, result.remaining_bytes), (1, 0));
+        assert!(!dir.join("old").exists());
+        assert!(dir.join(format!("{INTERNAL_ENTRY_PREFIX}file")).exists());
+        assert!(dir.join(format!("{INTERNAL_ENTRY_PREFIX}dir/a")).exists());
+    }
+}
@@ -0,0 +1,348 @@
+use std::io::{BufReader, Cursor, Read, Write};
+use crate::*;
+
+
+/// API of compressed files in [`PrivateStorage`].
+/// 
+/// This requires the `compression` feature.  
+/// Files are compressed by [`Compression`] when written,  
+/// and the format is detected from the contents when read.  
+/// Files that are not compressed, such as those written by [`PrivateStorage::write`], are read as is.
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::{AndroidFsExt, Compression, PrivateDir};
+///
+///     let api = app.android_fs();
+///     let storage = api.private_storage().compressed(Compression::Zstd);
+///
+///     storage.write(PrivateDir::Data, "logs/app.log", b"...").unwrap();
+///     let contents = storage.read(PrivateDir::Data, "logs/app.log").unwrap();
+/// }
+/// ```
+pub struct CompressedStorage<'a, R: tauri::Runtime> {
+    afs: &'a AndroidFs<R>,
+    compression: Compression,
+}
+
+impl<'a, R: tauri::Runtime> CompressedStorage<'a, R> {
+
+    pub(crate) fn new(afs: &'a AndroidFs<R>, compression: Compression) -> Self {
+        Self { afs, compression }
+    }
+
+    /// Compresses and writes a slice as the entire contents of a file.
+    /// 
+    /// This function will create a file if it does not exist, and will entirely replace its contents if it does.  
+    /// Recursively create parent directories if they are missing.  
+    /// The file is replaced atomically, so it is not corrupted even if the app is killed while writing.
+    /// 
+    /// # Support
+    /// All.
+    pub fn write(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+        contents: impl AsRef<[u8]>,
+    ) -> crate::Result<()> {
+
+        on_android!({
+            let mut writer = self.create_file(base_dir, relative_path)?;
+            writer.write_all(contents.as_ref())?;
+            writer.finish()?.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Reads and decompresses the entire contents of a file.  
+    /// If the file is not compressed, the contents are returned as is.
+    /// 
+    /// # Support
+    /// All.
+    pub fn read(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<Vec<u8>> {
+
+        on_android!({
+            let mut reader = self.open_file(base_dir, relative_path)?;
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            Ok(buf)
+        })
+    }
+
+    /// Reads and decompresses the entire contents of a file into a string.  
+    /// If the file is not compressed, the contents are returned as is.
+    /// 
+    /// # Support
+    /// All.
+    pub fn read_to_string(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<String> {
+
+        on_android!({
+            let mut reader = self.open_file(base_dir, relative_path)?;
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf)?;
+            Ok(buf)
+        })
+    }
+
+    /// Opens a file to read and decompress it in a streaming manner.  
+    /// If the file is not compressed, the contents are read as is.
+    /// 
+    /// # Support
+    /// All.
+    pub fn open_file(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<CompressedReader<std::fs::File>> {
+
+        on_android!({
+            let file = self.afs.private_storage().open_file(base_dir, relative_path)?;
+            Ok(CompressedReader::new(file)?)
+        })
+    }
+
+    /// Creates a file to write and compress it in a streaming manner.  
+    /// Recursively create parent directories if they are missing.
+    /// 
+    /// The contents are written to a temporary file,  
+    /// and the file is created or replaced only when [`AtomicWriteFile::commit`] is called on the result of [`CompressedWriter::finish`].  
+    /// If the writer is dropped before that, the existing file is not changed.
+    /// 
+    /// # Support
+    /// All.
+    pub fn create_file(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<CompressedWriter<AtomicWriteFile>> {
+
+        on_android!({
+            let path = self.afs.private_storage().resolve_path_with(base_dir, relative_path)?;
+            let file = AtomicWriteFile::create(path)?;
+            Ok(CompressedWriter::new(file, &self.compression)?)
+        })
+    }
+}
+
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Default level of gzip, same as `gzip` command.
+const GZIP_DEFAULT_LEVEL: u32 = 6;
+
+/// Default level of zstd, same as `zstd` command.
+const ZSTD_DEFAULT_LEVEL: i32 = 3;
+
+/// Writer that compresses the contents and writes it to the inner writer.
+/// 
+/// Call [`CompressedWriter::finish`] to write the end of the compressed stream and to know whether it succeeded.  
+/// If this is dropped without finishing, the written contents may be incomplete.
+pub struct CompressedWriter<W: Write> {
+    encoder: Encoder<W>,
+}
+
+enum Encoder<W: Write> {
+    Gzip(flate2::write::GzEncoder<W>),
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+
+    /// Creates a writer that compresses with ***compression***.
+    pub fn new(inner: W, compression: &Compression) -> std::io::Result<Self> {
+        let encoder = match compression {
+            Compression::Gzip => Encoder::Gzip(gzip_encoder(inner, GZIP_DEFAULT_LEVEL)),
+            Compression::GzipWith { level } => Encoder::Gzip(gzip_encoder(inner, *level)),
+            Compression::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(inner, ZSTD_DEFAULT_LEVEL)?),
+            Compression::ZstdWith { level } => Encoder::Zstd(zstd::stream::write::Encoder::new(inner, *level)?),
+        };
+
+        Ok(Self { encoder })
+    }
+
+    /// Writes the remaining compressed contents, and returns the inner writer.
+    pub fn finish(self) -> std::io::Result<W> {
+        let mut inner = match self.encoder {
+            Encoder::Gzip(e) => e.finish()?,
+            Encoder::Zstd(e) => e.finish()?,
+        };
+
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    fn encoder(&mut self) -> &mut dyn Write {
+        match &mut self.encoder {
+            Encoder::Gzip(e) => e,
+            Encoder::Zstd(e) => e,
+        }
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.encoder().write(data)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.encoder().flush()
+    }
+}
+
+fn gzip_encoder<W: Write>(inner: W, level: u32) -> flate2::write::GzEncoder<W> {
+    flate2::write::GzEncoder::new(inner, flate2::Compression::new(level.min(9)))
+}
+
+/// Reader that decompresses the contents of the inner reader.  
+/// The format is detected from the first bytes, and the contents that are not compressed are read as is.
+pub struct CompressedReader<I: Read> {
+    decoder: Decoder<I>,
+}
+
+/// The bytes read for detection are put back in front of the inner reader.
+type Sniffed<I> = std::io::Chain<Cursor<Vec<u8>>, I>;
+
+enum Decoder<I: Read> {
+    Plain(Sniffed<I>),
+    Gzip(flate2::read::MultiGzDecoder<Sniffed<I>>),
+    Zstd(zstd::stream::read::Decoder<'static, BufReader<Sniffed<I>>>),
+}
+
+impl<I: Read> CompressedReader<I> {
+
+    /// Reads the first bytes of ***inner*** to detect the format, and creates a reader that decompresses it.
+    pub fn new(mut inner: I) -> std::io::Result<Self> {
+        let mut head = Vec::with_capacity(ZSTD_MAGIC.len());
+        (&mut inner).take(ZSTD_MAGIC.len() as u64).read_to_end(&mut head)?;
+
+        let is_gzip = head.starts_with(GZIP_MAGIC);
+        let is_zstd = head.starts_with(ZSTD_MAGIC);
+        let inner = Cursor::new(head).chain(inner);
+
+        let decoder = if is_gzip {
+            Decoder::Gzip(flate2::read::MultiGzDecoder::new(inner))
+        }
+        else if is_zstd {
+            Decoder::Zstd(zstd::stream::read::Decoder::new(inner)?)
+        }
+        else {
+            Decoder::Plain(inner)
+        };
+
+        Ok(Self { decoder })
+    }
+
+    /// Returns the detected format, or None if the contents are not compressed.  
+    /// The level is not detected, so this is [`Compression::Gzip`] or [`Compression::Zstd`].
+    pub fn compression(&self) -> Option<Compression> {
+        match &self.decoder {
+            Decoder::Plain(_) => None,
+            Decoder::Gzip(_) => Some(Compression::Gzip),
+            Decoder::Zstd(_) => Some(Compression::Zstd),
+        }
+    }
+}
+
+impl<I: Read> Read for CompressedReader<I> {
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match &mut self.decoder {
+            Decoder::Plain(r) => r.read(buf),
+            Decoder::Gzip(r) => r.read(buf),
+            Decoder::Zstd(r) => r.read(buf),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compress(contents: &[u8], compression: &Compression) -> Vec<u8> {
+        let mut writer = CompressedWriter::new(Vec::new(), compression).unwrap();
+        writer.write_all(contents).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn decompress(bytes: &[u8]) -> (Option<Compression>, Vec<u8>) {
+        let mut reader = CompressedReader::new(bytes).unwrap();
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+        (reader.compression(), buf)
+    }
+
+    fn contents() -> Vec<u8> {
+        b"contents of the file. ".repeat(1000)
+    }
+
+    #[test]
+    fn detects_gzip() {
+        for compression in [Compression::Gzip, Compression::GzipWith { level: 1 }] {
+            let compressed = compress(&contents(), &compression);
+
+            assert!(compressed.starts_with(GZIP_MAGIC));
+            assert_eq!(decompress(&compressed), (Some(Compression::Gzip), contents()));
+        }
+    }
+
+    #[test]
+    fn detects_zstd() {
+        for compression in [Compression::Zstd, Compression::ZstdWith { level: 19 }] {
+            let compressed = compress(&contents(), &compression);
+
+            assert!(compressed.starts_with(ZSTD_MAGIC));
+            assert_eq!(decompress(&compressed), (Some(Compression::Zstd), contents()));
+        }
+    }
+
+    #[test]
+    fn reads_empty_contents() {
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            assert_eq!(decompress(&compress(b"", &compression)).1, b"");
+        }
+        assert_eq!(decompress(b""), (None, Vec::new()));
+    }
+
+    #[test]
+    fn reads_plain_contents_as_is() {
+        // Shorter than the magic, starting with a part of the magic, and ordinary text
+        let plains: [&[u8]; 4] = [b"a", &[0x1f], &[0x28, 0xb5, 0x2f], b"plain text written by PrivateStorage::write"];
+
+        for plain in plains {
+            assert_eq!(decompress(plain), (None, plain.to_vec()));
+        }
+    }
+
+    #[test]
+    fn reads_concatenated_gzip_members() {
+        let mut compressed = compress(b"first ", &Compression::Gzip);
+        compressed.extend(compress(b"second", &Compression::Gzip));
+
+        assert_eq!(decompress(&compressed).1, b"first second");
+    }
+
+    #[test]
+    fn unfinished_writer_is_incomplete() {
+        let mut compressed = Vec::new();
+        {
+            let mut writer = CompressedWriter::new(&mut compressed, &Compression::Zstd).unwrap();
+            writer.write_all(&contents()).unwrap();
+        }
+
+        let mut buf = Vec::new();
+        let result = CompressedReader::new(compressed.as_slice()).and_then(|mut r| r.read_to_end(&mut buf));
+        assert!(result.is_err() || buf != contents());
+    }
+}
@@ -0,0 +1,452 @@
+use std::io::{Read, Write};
+use std::sync::Arc;
+use aes_gcm::{Aes256Gcm, KeyInit as _};
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use crate::*;
+
+
+/// API of encrypted files in [`PrivateStorage`].
+/// 
+/// This requires the `encryption` feature.  
+/// Files are encrypted by AES-256-GCM in chunks, so large files can be read and written without loading the entire contents.  
+/// Each chunk is authenticated, and truncation or reordering of chunks is also detected.
+/// 
+/// By default, the key is generated randomly for this app and wrapped by a key in Android Keystore,  
+/// which cannot be extracted from the device.  
+/// So the files cannot be decrypted after the app data is restored on another device, or the app is reinstalled.  
+/// To use another key, use [`PrivateStorage::encrypted_with`].
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::{AndroidFsExt, PrivateDir};
+///
+///     let api = app.android_fs();
+///     let storage = api.private_storage().encrypted();
+///
+///     storage.write(PrivateDir::Data, "credentials.bin", b"secret").unwrap();
+///     let contents = storage.read(PrivateDir::Data, "credentials.bin").unwrap();
+/// }
+/// ```
+pub struct EncryptedStorage<'a, R: tauri::Runtime> {
+    afs: &'a AndroidFs<R>,
+    key_provider: Option<Arc<dyn KeyProvider>>,
+}
+
+/// Provider of the 256-bit key used by [`EncryptedStorage`].
+/// 
+/// This can be used to manage the key by yourself, or to test with a software key on other than Android.
+pub trait KeyProvider: Send + Sync {
+
+    /// Returns the key.  
+    /// The same key must be returned each time, otherwise the files cannot be decrypted.
+    fn key(&self) -> crate::Result<[u8; 32]>;
+}
+
+/// [`KeyProvider`] that returns the specified key as is.
+#[derive(Clone)]
+pub struct SoftwareKeyProvider {
+    key: [u8; 32],
+}
+
+impl SoftwareKeyProvider {
+
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+}
+
+impl KeyProvider for SoftwareKeyProvider {
+
+    fn key(&self) -> crate::Result<[u8; 32]> {
+        Ok(self.key)
+    }
+}
+
+impl std::fmt::Debug for SoftwareKeyProvider {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SoftwareKeyProvider").finish_non_exhaustive()
+    }
+}
+
+impl<'a, R: tauri::Runtime> EncryptedStorage<'a, R> {
+
+    pub(crate) fn new(afs: &'a AndroidFs<R>, key_provider: Option<Arc<dyn KeyProvider>>) -> Self {
+        Self { afs, key_provider }
+    }
+
+    /// Encrypts and writes a slice as the entire contents of a file.
+    /// 
+    /// This function will create a file if it does not exist, and will entirely replace its contents if it does.  
+    /// Recursively create parent directories if they are missing.  
+    /// The file is replaced atomically, so it is not corrupted even if the app is killed while writing.
+    /// 
+    /// # Support
+    /// All.
+    pub fn write(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+        contents: impl AsRef<[u8]>,
+    ) -> crate::Result<()> {
+
+        on_android!({
+            let mut writer = self.create_file(base_dir, relative_path)?;
+            writer.write_all(contents.as_ref())?;
+            writer.finish()?.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Reads and decrypts the entire contents of a file written by [`EncryptedStorage`].
+    /// 
+    /// An error occurs if the file is not encrypted, is encrypted with another key, or has been tampered with.
+    /// 
+    /// # Support
+    /// All.
+    pub fn read(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<Vec<u8>> {
+
+        on_android!({
+            let mut reader = self.open_file(base_dir, relative_path)?;
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            Ok(buf)
+        })
+    }
+
+    /// Opens a file written by [`EncryptedStorage`] to read and decrypt it in a streaming manner.
+    /// 
+    /// Since each chunk is verified when it is read,  
+    /// an error may occur in the middle of reading if the file has been tampered with.
+    /// 
+    /// # Support
+    /// All.
+    pub fn open_file(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<EncryptedReader<std::fs::File>> {
+
+        on_android!({
+            let path = self.afs.private_storage().resolve_path_with(base_dir, relative_path)?;
+            let key = self.key()?;
+            let file = std::fs::File::open(path)?;
+            Ok(EncryptedReader::new(file, &key)?)
+        })
+    }
+
+    /// Creates a file to write and encrypt it in a streaming manner.  
+    /// Recursively create parent directories if they are missing.
+    /// 
+    /// The contents are written to a temporary file,  
+    /// and the file is created or replaced only when [`AtomicWriteFile::commit`] is called on the result of [`EncryptedWriter::finish`].  
+    /// If the writer is dropped before that, the existing file is not changed.
+    /// 
+    /// # Support
+    /// All.
+    pub fn create_file(
+        &self,
+        base_dir: PrivateDir,
+        relative_path: impl AsRef<str>,
+    ) -> crate::Result<EncryptedWriter<AtomicWriteFile>> {
+
+        on_android!({
+            let path = self.afs.private_storage().resolve_path_with(base_dir, relative_path)?;
+            let key = self.key()?;
+            let file = AtomicWriteFile::create(path)?;
+            Ok(EncryptedWriter::new(file, &key)?)
+        })
+    }
+
+
+    #[cfg(target_os = "android")]
+    fn key(&self) -> crate::Result<[u8; KEY_LEN]> {
+        if let Some(key_provider) = &self.key_provider {
+            return key_provider.key()
+        }
+
+        let mut cached = self.afs.encryption_key.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(key) = *cached {
+            return Ok(key)
+        }
+
+        impl_se!(struct Req { path: String });
+        impl_de!(struct Res { key: Vec<u8> });
+
+        let path = self.afs.private_storage().resolve_path_with(PrivateDir::Data, ENCRYPTION_KEY_FILE_RELATIVE_PATH)?;
+        let key = self.afs.api
+            .run_mobile_plugin::<Res>("getEncryptionKey", Req { path: path.to_string_lossy().into_owned() })?
+            .key;
+
+        let key: [u8; KEY_LEN] = key.try_into()
+            .map_err(|_| crate::Error::PluginInvoke("Invalid length of the encryption key.".into()))?;
+
+        *cached = Some(key);
+        Ok(key)
+    }
+}
+
+
+/// Length of the key in bytes.
+const KEY_LEN: usize = 32;
+
+/// Identifies the format of the encrypted files.
+const MAGIC: &[u8; 8] = b"AFSENC\x00\x01";
+
+/// Length of the nonce for STREAM construction with 32-bit counter.
+const NONCE_LEN: usize = 7;
+
+/// Length of plaintext in each chunk, except the last one.
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// Length of the authentication tag of each chunk.
+const TAG_LEN: usize = 16;
+
+/// Writer that encrypts the contents and writes it to the inner writer.
+/// 
+/// Format is the header (magic and nonce) followed by chunks.  
+/// Each chunk except the last one has exactly 64 KiB of plaintext,  
+/// and the last one has less than that, so that the reader can find the last chunk by its length.
+/// 
+/// The last chunk is written only by [`EncryptedWriter::finish`].  
+/// If this is dropped without finishing, the written contents cannot be decrypted.
+pub struct EncryptedWriter<W: Write> {
+    inner: W,
+    encryptor: EncryptorBE32<Aes256Gcm>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> EncryptedWriter<W> {
+
+    /// Writes the header to ***inner***, and creates a writer that encrypts with ***key***.
+    pub fn new(mut inner: W, key: &[u8; 32]) -> std::io::Result<Self> {
+        use aes_gcm::aead::{rand_core::RngCore as _, OsRng};
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.try_fill_bytes(&mut nonce).map_err(|e| std::io::Error::other(e.to_string()))?;
+
+        inner.write_all(MAGIC)?;
+        inner.write_all(&nonce)?;
+
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
+        let encryptor = EncryptorBE32::from_aead(cipher, GenericArray::from_slice(&nonce));
+
+        Ok(Self { inner, encryptor, buf: Vec::with_capacity(CHUNK_LEN) })
+    }
+
+    /// Encrypts and writes the remaining contents as the last chunk, and returns the inner writer.
+    pub fn finish(self) -> std::io::Result<W> {
+        let Self { mut inner, encryptor, buf } = self;
+
+        let chunk = encryptor.encrypt_last(buf.as_slice()).map_err(|_| encryption_error())?;
+        inner.write_all(&chunk)?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for EncryptedWriter<W> {
+
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        let len = data.len().min(CHUNK_LEN - self.buf.len());
+        self.buf.extend_from_slice(&data[..len]);
+
+        // Even if no more data follows, this is not the last chunk.
+        // The last chunk is written by finish, and it is empty in that case.
+        if self.buf.len() == CHUNK_LEN {
+            let chunk = self.encryptor.encrypt_next(self.buf.as_slice()).map_err(|_| encryption_error())?;
+            self.buf.clear();
+            self.inner.write_all(&chunk)?;
+        }
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader that decrypts the contents written by [`EncryptedWriter`].
+pub struct EncryptedReader<I: Read> {
+    inner: I,
+    decryptor: Option<DecryptorBE32<Aes256Gcm>>,
+    plain: Vec<u8>,
+    pos: usize,
+}
+
+impl<I: Read> EncryptedReader<I> {
+
+    /// Reads the header from ***inner***, and creates a reader that decrypts with ***key***.
+    pub fn new(mut inner: I, key: &[u8; 32]) -> std::io::Result<Self> {
+        let mut magic = [0u8; MAGIC.len()];
+        let mut nonce = [0u8; NONCE_LEN];
+
+        inner.read_exact(&mut magic).map_err(|_| invalid_format_error())?;
+        if &magic != MAGIC {
+            return Err(invalid_format_error())
+        }
+        inner.read_exact(&mut nonce).map_err(|_| invalid_format_error())?;
+
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
+        let decryptor = DecryptorBE32::from_aead(cipher, GenericArray::from_slice(&nonce));
+
+        Ok(Self { inner, decryptor: Some(decryptor), plain: Vec::new(), pos: 0 })
+    }
+
+    /// Reads and decrypts the next chunk.
+    fn fill(&mut self) -> std::io::Result<()> {
+        let mut chunk = vec![0u8; CHUNK_LEN + TAG_LEN];
+        let len = read_full(&mut self.inner, &mut chunk)?;
+        chunk.truncate(len);
+
+        // Only the last chunk is shorter than the others.
+        let plain = match len == CHUNK_LEN + TAG_LEN {
+            true => self.decryptor.as_mut().unwrap().decrypt_next(chunk.as_slice()),
+            false => self.decryptor.take().unwrap().decrypt_last(chunk.as_slice()),
+        };
+
+        self.plain = plain.map_err(|_| decryption_error())?;
+
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<I: Read> Read for EncryptedReader<I> {
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.plain.len() {
+            if self.decryptor.is_none() {
+                return Ok(0)
+            }
+            self.fill()?;
+        }
+
+        let len = buf.len().min(self.plain.len() - self.pos);
+        buf[..len].copy_from_slice(&self.plain[self.pos..(self.pos + len)]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+/// Reads until ***buf*** is full or EOF, and returns the number of bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match reader.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(total)
+}
+
+fn encryption_error() -> std::io::Error {
+    std::io::Error::other("Failed to encrypt.")
+}
+
+fn decryption_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to decrypt. The file may have been tampered with, or the key is different.")
+}
+
+fn invalid_format_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "The file is not encrypted by EncryptedStorage.")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> [u8; KEY_LEN] {
+        SoftwareKeyProvider::new([byte; KEY_LEN]).key().unwrap()
+    }
+
+    fn encrypt(contents: &[u8], key: &[u8; KEY_LEN]) -> Vec<u8> {
+        let mut writer = EncryptedWriter::new(Vec::new(), key).unwrap();
+        writer.write_all(contents).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn decrypt(encrypted: &[u8], key: &[u8; KEY_LEN]) -> std::io::Result<Vec<u8>> {
+        let mut reader = EncryptedReader::new(encrypted, key)?;
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn contents(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for len in [0, 1, CHUNK_LEN - 1, CHUNK_LEN, CHUNK_LEN + 1, 2 * CHUNK_LEN, 3 * CHUNK_LEN + 5] {
+            let contents = contents(len);
+            let encrypted = encrypt(&contents, &key(1));
+
+            assert_eq!(decrypt(&encrypted, &key(1)).unwrap(), contents, "len: {len}");
+        }
+    }
+
+    #[test]
+    fn exact_multiple_of_chunk_ends_with_empty_chunk() {
+        let encrypted = encrypt(&contents(2 * CHUNK_LEN), &key(1));
+
+        assert_eq!(encrypted.len(), MAGIC.len() + NONCE_LEN + 3 * TAG_LEN + 2 * CHUNK_LEN);
+    }
+
+    #[test]
+    fn detects_truncation() {
+        let encrypted = encrypt(&contents(2 * CHUNK_LEN + 10), &key(1));
+        let header_len = MAGIC.len() + NONCE_LEN;
+
+        // In the middle of the last chunk, at the end of a full chunk, and in the middle of a full chunk
+        for len in [encrypted.len() - 1, header_len + 2 * (CHUNK_LEN + TAG_LEN), header_len + CHUNK_LEN] {
+            assert!(decrypt(&encrypted[..len], &key(1)).is_err(), "len: {len}");
+        }
+        assert!(decrypt(&encrypted[..header_len - 1], &key(1)).is_err());
+    }
+
+    #[test]
+    fn detects_truncation_of_exact_multiple() {
+        let encrypted = encrypt(&contents(CHUNK_LEN), &key(1));
+
+        // Without the empty last chunk
+        assert!(decrypt(&encrypted[..encrypted.len() - TAG_LEN], &key(1)).is_err());
+    }
+
+    #[test]
+    fn fails_with_wrong_key() {
+        for len in [0, 10, CHUNK_LEN + 10] {
+            let encrypted = encrypt(&contents(len), &key(1));
+
+            assert!(decrypt(&encrypted, &key(2)).is_err(), "len: {len}");
+        }
+    }
+
+    #[test]
+    fn rejects_unencrypted_contents() {
+        assert!(decrypt(b"plain text contents", &key(1)).is_err());
+    }
+
+    #[test]
+    fn unfinished_writer_cannot_be_decrypted() {
+        let mut encrypted = Vec::new();
+        {
+            let mut writer = EncryptedWriter::new(&mut encrypted, &key(1)).unwrap();
+            writer.write_all(&contents(CHUNK_LEN + 10)).unwrap();
+        }
+
+        assert!(decrypt(&encrypted, &key(1)).is_err());
+    }
+}
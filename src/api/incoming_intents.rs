@@ -0,0 +1,243 @@
+use crate::*;
+
+
+/// API of the files and text shared to this app by other apps.
+/// 
+/// This captures `ACTION_SEND`, `ACTION_SEND_MULTIPLE` and `ACTION_VIEW` intents,  
+/// both the one that launched the app and the ones received while running.  
+/// Received intents are kept as pending, even across app restarts, until consumed by [`IncomingIntents::take_pending`] or [`IncomingIntents::consume`].
+/// 
+/// To receive them, the app needs to declare the intent filters in `AndroidManifest.xml`, such as following.
+/// ```xml
+/// <activity ...>
+///     <intent-filter>
+///         <action android:name="android.intent.action.SEND" />
+///         <action android:name="android.intent.action.SEND_MULTIPLE" />
+///         <category android:name="android.intent.category.DEFAULT" />
+///         <data android:mimeType="image/*" />
+///     </intent-filter>
+/// </activity>
+/// ```
+/// 
+/// # Note
+/// The permissions of the received URIs are valid until the app is terminated in most cases.  
+/// So pending intents restored after the app restarts may no longer be accessible.  
+/// If you need to keep access, copy the files or use [`AndroidFs::take_persistable_uri_permission`] if possible.
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::AndroidFsExt;
+///
+///     let api = app.android_fs();
+///     let incoming_intents = api.incoming_intents();
+///
+///     // Intents received before this
+///     for intent in incoming_intents.take_pending().unwrap() {
+///         // handle intent.uris and intent.text
+///     }
+///
+///     // Intents received after this
+///     let app = app.clone();
+///     incoming_intents.on_received(move |intent| {
+///         let _ = app.android_fs().incoming_intents().consume(&intent.id);
+///         // handle intent.uris and intent.text
+///     }).unwrap();
+/// }
+/// ```
+pub struct IncomingIntents<'a, R: tauri::Runtime>(pub(crate) &'a AndroidFs<R>);
+
+#[derive(Default)]
+pub(crate) struct IncomingIntentsState {
+    callbacks: Vec<IncomingIntentCallback>,
+}
+
+/// Callback registered by [`IncomingIntents::on_received`].
+type IncomingIntentCallback = std::sync::Arc<dyn Fn(&IncomingIntent) + Send + Sync>;
+
+/// Event emitted with [`IncomingIntent`] as payload when an intent is received.
+pub const INCOMING_INTENT_EVENT: &str = "android-fs://incoming-intent";
+
+impl<'a, R: tauri::Runtime> IncomingIntents<'a, R> {
+
+    /// Gets the pending intents in the order they were received, without consuming them.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_pending(&self) -> crate::Result<Vec<IncomingIntent>> {
+        on_android!({
+            let _state = self.lock();
+            self.load()
+        })
+    }
+
+    /// Gets and consumes the pending intents in the order they were received.
+    /// 
+    /// # Support
+    /// All.
+    pub fn take_pending(&self) -> crate::Result<Vec<IncomingIntent>> {
+        on_android!({
+            let _state = self.lock();
+            let intents = self.load()?;
+            self.save(&[])?;
+            Ok(intents)
+        })
+    }
+
+    /// Consumes the pending intent of the id.  
+    /// Returns false if it is not pending.
+    /// 
+    /// # Support
+    /// All.
+    pub fn consume(&self, id: &str) -> crate::Result<bool> {
+        on_android!({
+            let _state = self.lock();
+            let mut intents = self.load()?;
+            let len = intents.len();
+
+            intents.retain(|i| i.id != id);
+            if intents.len() == len {
+                return Ok(false)
+            }
+
+            self.save(&intents)?;
+            Ok(true)
+        })
+    }
+
+    /// Registers a callback that is called each time an intent is received.  
+    /// The intent remains pending even after the callback is called,  
+    /// so consume it by [`IncomingIntents::consume`] when it has been handled.
+    /// 
+    /// Intents received before this is registered are not passed to the callback.  
+    /// Use [`IncomingIntents::take_pending`] for them.
+    /// 
+    /// The event [`INCOMING_INTENT_EVENT`] is also emitted to the frontend.
+    /// 
+    /// # Support
+    /// All.
+    pub fn on_received(&self, callback: impl Fn(&IncomingIntent) + Send + Sync + 'static) -> crate::Result<()> {
+        on_android!({
+            self.lock().callbacks.push(std::sync::Arc::new(callback));
+            Ok(())
+        })
+    }
+
+
+    /// Starts receiving intents from the plugin.  
+    /// This is called once when the plugin is initialized.
+    #[cfg(target_os = "android")]
+    pub(crate) fn listen(&self) -> crate::Result<()> {
+        use tauri::Manager as _;
+
+        impl_se!(struct Req { channel: tauri::ipc::Channel });
+        impl_de!(struct Res;);
+
+        let app = self.0.app.clone();
+        let channel = tauri::ipc::Channel::new(move |body| {
+            if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                if let Ok(msg) = serde_json::from_str::<Msg>(&json) {
+                    app.android_fs().incoming_intents().on_message(msg);
+                }
+            }
+            Ok(())
+        });
+
+        self.0.api
+            .run_mobile_plugin::<Res>("registerIncomingIntentListener", Req { channel })
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    #[cfg(target_os = "android")]
+    fn on_message(&self, msg: Msg) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tauri::Emitter as _;
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let received_at = std::time::SystemTime::now();
+        let id = format!(
+            "{}-{}",
+            received_at.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let intent = IncomingIntent {
+            id,
+            action: msg.action,
+            uris: msg.uris,
+            mime_type: msg.mime_type,
+            text: msg.text,
+            subject: msg.subject,
+            received_at,
+        };
+
+        let callbacks = {
+            let state = self.lock();
+
+            // If the file is unreadable, such as corrupted, start over rather than losing the new intent.
+            let mut intents = self.load().unwrap_or_default();
+            intents.push(intent.clone());
+
+            // Even if this fails, the intent is still passed to the callbacks and the frontend.
+            let _ = self.save(&intents);
+
+            state.callbacks.clone()
+        };
+
+        for callback in callbacks {
+            callback(&intent);
+        }
+
+        let _ = self.0.app.emit(INCOMING_INTENT_EVENT, &intent);
+    }
+
+    fn load(&self) -> crate::Result<Vec<IncomingIntent>> {
+        let path = self.resolve_file_path()?;
+
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, intents: &[IncomingIntent]) -> crate::Result<()> {
+        use std::io::Write as _;
+
+        let path = self.resolve_file_path()?;
+
+        if intents.is_empty() {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        }
+
+        let mut tmp_file = AtomicWriteFile::create(path)?;
+        tmp_file.write_all(&serde_json::to_vec(intents)?)?;
+        tmp_file.commit()?;
+        Ok(())
+    }
+
+    fn resolve_file_path(&self) -> crate::Result<std::path::PathBuf> {
+        self.0.private_storage().resolve_path_with(PrivateDir::Data, INCOMING_INTENTS_FILE_RELATIVE_PATH)
+    }
+
+    #[cfg(target_os = "android")]
+    fn lock(&self) -> std::sync::MutexGuard<'_, IncomingIntentsState> {
+        self.0.incoming_intents_state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Msg {
+    action: IncomingIntentAction,
+    uris: Vec<FileUri>,
+    mime_type: Option<String>,
+    text: Option<String>,
+    subject: Option<String>,
+}
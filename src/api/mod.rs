@@ -44,7 +44,74 @@ macro_rules! impl_de {
 mod android_fs;
 mod private_storage;
 mod public_storage;
+mod thumbnail_cache;
+mod permission_registry;
+mod incoming_intents;
+mod temp;
+mod cache_manager;
+mod store;
 
-pub use android_fs::AndroidFs;
+#[cfg(feature = "protocol")]
+mod webview_protocol;
+
+#[cfg(feature = "watch")]
+mod watcher;
+
+#[cfg(feature = "encryption")]
+mod encrypted_storage;
+
+#[cfg(feature = "compression")]
+mod compressed_storage;
+
+#[cfg(feature = "archive")]
+mod archive;
+
+#[cfg(feature = "backup")]
+mod backup;
+
+#[cfg(test)]
+mod test_util;
+
+pub use android_fs::{AndroidFs, STORAGE_VOLUME_CHANGED_EVENT};
 pub use private_storage::PrivateStorage;
-pub use public_storage::PublicStorage;
\ No newline at end of file
+pub use public_storage::PublicStorage;
+pub use thumbnail_cache::ThumbnailCache;
+pub use permission_registry::PermissionRegistry;
+pub use incoming_intents::{IncomingIntents, INCOMING_INTENT_EVENT};
+pub use temp::{TempFile, TempDir, AtomicWriteFile};
+pub use cache_manager::CacheManager;
+pub use store::{Store, STORE_CHANGED_EVENT};
+
+#[cfg(feature = "protocol")]
+pub use webview_protocol::WebviewProtocol;
+
+#[cfg(feature = "watch")]
+pub use watcher::{WatchHandle, FILE_CHANGE_EVENT};
+
+#[cfg(feature = "watch")]
+pub(crate) use watcher::WatcherState;
+
+#[cfg(feature = "encryption")]
+pub use encrypted_storage::{EncryptedStorage, EncryptedReader, EncryptedWriter, KeyProvider, SoftwareKeyProvider};
+
+#[cfg(feature = "compression")]
+pub use compressed_storage::{CompressedStorage, CompressedReader, CompressedWriter};
+
+#[cfg(all(feature = "watch", target_os = "android"))]
+pub(crate) use watcher::watch;
+
+#[cfg(all(feature = "archive", target_os = "android"))]
+pub(crate) use archive::{zip_dir, unzip};
+
+#[cfg(all(feature = "backup", target_os = "android"))]
+pub(crate) use backup::{export_private_data, import_private_data};
+
+#[cfg(feature = "protocol")]
+pub(crate) use webview_protocol::PROTOCOL_SCHEME;
+
+pub(crate) use thumbnail_cache::ThumbnailCacheState;
+pub(crate) use permission_registry::PermissionRegistryState;
+pub(crate) use incoming_intents::IncomingIntentsState;
+pub(crate) use cache_manager::CacheManagerState;
+pub(crate) use store::StoreState;
+pub(crate) use temp::{create_temp_file, create_temp_dir, remove_stale_temp_files};
\ No newline at end of file
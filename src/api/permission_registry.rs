@@ -0,0 +1,441 @@
+use crate::*;
+
+
+/// API of the registry of persisted URI permissions, stored in [`PrivateDir::Data`].
+/// 
+/// [`AndroidFs::take_persistable_uri_permission`] and [`AndroidFs::get_all_persisted_uri_permissions`] only handle raw URIs.  
+/// This registry associates them with app-defined keys, labels and timestamps,  
+/// such as `"backup-dir"` for the directory that the user selected as the backup destination.
+/// 
+/// The registered entries are revalidated in the background when the app starts.  
+/// Entries whose permission was revoked or whose target no longer exists can be retrieved by [`PermissionRegistry::get_all_invalid`].
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::AndroidFsExt;
+///
+///     let api = app.android_fs();
+///     let registry = api.permission_registry();
+///
+///     // Register a directory selected by the user.
+///     let Some(uri) = api.show_manage_dir_dialog(None).unwrap() else {
+///         return
+///     };
+///     registry.register("backup-dir", &uri, Some("Backup")).unwrap();
+///
+///     // After the app restarts
+///     if let Some(entry) = registry.get("backup-dir").unwrap() {
+///         if entry.is_invalid() {
+///             // Ask the user to select the directory again.
+///         }
+///     }
+/// }
+/// ```
+pub struct PermissionRegistry<'a, R: tauri::Runtime>(pub(crate) &'a AndroidFs<R>);
+
+#[derive(Default)]
+pub(crate) struct PermissionRegistryState {
+    eviction_policy: PersistedUriPermissionEvictionPolicy,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryFile {
+    version: u32,
+    entries: Vec<RegisteredUriPermission>,
+}
+
+impl<'a, R: tauri::Runtime> PermissionRegistry<'a, R> {
+
+    /// Takes persistent permission to access the file or directory by [`AndroidFs::take_persistable_uri_permission`],  
+    /// and registers it with the key.  
+    /// If the key is already registered, the entry is replaced.
+    /// 
+    /// The returned entry is validated,  
+    /// so check [`RegisteredUriPermission::status`] to know whether the permission was actually persisted.
+    /// 
+    /// If the limit of persisted URI permissions is reached,  
+    /// a permission is relinquished according to [`PermissionRegistry::set_eviction_policy`] before taking the new one.  
+    /// If nothing can be relinquished, this returns [`Error::PersistedUriPermissionLimitReached`].
+    /// 
+    /// # Args
+    /// - ***key*** :  
+    /// App-defined key.
+    /// 
+    /// - ***uri*** :  
+    /// URI of the target file or directory.  
+    /// See [`AndroidFs::take_persistable_uri_permission`] for details.
+    /// 
+    /// - ***label*** :  
+    /// App-defined label.
+    /// 
+    /// # Support
+    /// All.
+    pub fn register(
+        &self,
+        key: impl Into<String>,
+        uri: &FileUri,
+        label: Option<&str>,
+    ) -> crate::Result<RegisteredUriPermission> {
+
+        on_android!({
+            let key = key.into();
+
+            // This relinquishes a permission according to the policy if the limit is reached.
+            self.0.take_persistable_uri_permission(uri)?;
+
+            let _state = self.lock();
+            let mut file = self.load()?;
+
+            let persisted = self.0.get_all_persisted_uri_permissions()?.collect::<Vec<_>>();
+            let mut entry = RegisteredUriPermission {
+                uri: uri.clone(),
+                label: label.map(Into::into),
+                registered_at: std::time::SystemTime::now(),
+                validated_at: None,
+                status: RegisteredUriPermissionStatus::Unchecked,
+                last_used_at: None,
+                pinned: file.entries.iter().any(|e| e.key == key && e.pinned),
+                key,
+            };
+            self.validate_entry(&mut entry, &persisted);
+
+            file.entries.retain(|e| e.key != entry.key);
+            file.entries.push(entry.clone());
+            self.save(&file)?;
+
+            Ok(entry)
+        })
+    }
+
+    /// Gets the entry of the key.  
+    /// If the key is not registered, returns `None`.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get(&self, key: &str) -> crate::Result<Option<RegisteredUriPermission>> {
+        on_android!({
+            let _state = self.lock();
+            Ok(self.load()?.entries.into_iter().find(|e| e.key == key))
+        })
+    }
+
+    /// Gets all registered entries in the order they were registered.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_all(&self) -> crate::Result<Vec<RegisteredUriPermission>> {
+        on_android!({
+            let _state = self.lock();
+            Ok(self.load()?.entries)
+        })
+    }
+
+    /// Gets all entries whose permission was revoked or whose target no longer exists at the last validation.  
+    /// This does not revalidate. If you need the latest status, use [`PermissionRegistry::validate`] instead.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_all_invalid(&self) -> crate::Result<Vec<RegisteredUriPermission>> {
+        on_android!({
+            Ok(self.get_all()?.into_iter().filter(|e| e.is_invalid()).collect())
+        })
+    }
+
+    /// Unregisters the entry of the key, and returns it.  
+    /// If the key is not registered, returns `None`.
+    /// 
+    /// # Args
+    /// - ***release*** :  
+    /// If true, the persisted permission is also relinquished by [`AndroidFs::release_persisted_uri_permission`].  
+    /// However, if another entry has the same URI, the permission is kept.
+    /// 
+    /// # Support
+    /// All.
+    pub fn unregister(&self, key: &str, release: bool) -> crate::Result<Option<RegisteredUriPermission>> {
+        on_android!({
+            let _state = self.lock();
+            let mut file = self.load()?;
+
+            let Some(i) = file.entries.iter().position(|e| e.key == key) else {
+                return Ok(None)
+            };
+            let entry = file.entries.remove(i);
+            self.save(&file)?;
+
+            let target = grant_target(&entry.uri);
+            if release && !file.entries.iter().any(|e| grant_target(&e.uri) == target) {
+                self.0.release_persisted_uri_permission(&entry.uri)?;
+            }
+
+            Ok(Some(entry))
+        })
+    }
+
+    /// Revalidates all entries against [`AndroidFs::get_all_persisted_uri_permissions`] and updates their status.  
+    /// Then returns the entries whose permission was revoked or whose target no longer exists.
+    /// 
+    /// This is also done in the background when the app starts.
+    /// 
+    /// # Support
+    /// All.
+    pub fn validate(&self) -> crate::Result<Vec<RegisteredUriPermission>> {
+        on_android!({
+            let _state = self.lock();
+            let mut file = self.load()?;
+            if file.entries.is_empty() {
+                return Ok(Vec::new())
+            }
+
+            let persisted = self.0.get_all_persisted_uri_permissions()?.collect::<Vec<_>>();
+            for entry in &mut file.entries {
+                self.validate_entry(entry, &persisted);
+            }
+            self.save(&file)?;
+
+            Ok(file.entries.into_iter().filter(|e| e.is_invalid()).collect())
+        })
+    }
+
+
+    /// Marks the entry of the key as used now.  
+    /// This is used by [`PersistedUriPermissionEvictionPolicy::LeastRecentlyUsed`].  
+    /// Returns false if the key is not registered.
+    /// 
+    /// # Support
+    /// All.
+    pub fn touch(&self, key: &str) -> crate::Result<bool> {
+        on_android!({
+            self.update(key, |e| e.last_used_at = Some(std::time::SystemTime::now()))
+        })
+    }
+
+    /// Sets whether the entry of the key is pinned.  
+    /// The permission of a pinned entry is never relinquished by eviction.  
+    /// Returns false if the key is not registered.
+    /// 
+    /// # Support
+    /// All.
+    pub fn set_pinned(&self, key: &str, pinned: bool) -> crate::Result<bool> {
+        on_android!({
+            self.update(key, |e| e.pinned = pinned)
+        })
+    }
+
+    /// Sets the policy for relinquishing persisted URI permissions when the limit is reached.
+    /// 
+    /// This is applied before taking a new permission by [`PermissionRegistry::register`],  
+    /// [`AndroidFs::take_persistable_uri_permission`] and [`AndroidFs::take_persistable_uri_permission_with`].  
+    /// If nothing can be relinquished, they return [`Error::PersistedUriPermissionLimitReached`].
+    /// 
+    /// This is not persisted, so please set it each time the app starts.  
+    /// By default, [`PersistedUriPermissionEvictionPolicy::None`] is used.
+    /// 
+    /// # Support
+    /// All.
+    pub fn set_eviction_policy(&self, policy: PersistedUriPermissionEvictionPolicy) -> crate::Result<()> {
+        on_android!({
+            self.lock().eviction_policy = policy;
+            Ok(())
+        })
+    }
+
+    /// Gets the policy set by [`PermissionRegistry::set_eviction_policy`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn eviction_policy(&self) -> crate::Result<PersistedUriPermissionEvictionPolicy> {
+        on_android!({
+            Ok(self.lock().eviction_policy)
+        })
+    }
+
+    /// Relinquishes persisted URI permissions according to the policy, and returns their URIs.  
+    /// This targets all permissions persisted by [`AndroidFs::take_persistable_uri_permission`], not only the registered ones.  
+    /// However, the permissions of pinned entries are never relinquished.
+    /// 
+    /// Registered entries whose permission was relinquished are kept with [`RegisteredUriPermissionStatus::Revoked`].
+    /// 
+    /// # Args
+    /// - ***count*** :  
+    /// Maximum number of permissions to relinquish.
+    /// 
+    /// - ***policy*** :  
+    /// Policy to select the permissions.  
+    /// If this is [`PersistedUriPermissionEvictionPolicy::None`], nothing is relinquished.
+    /// 
+    /// # Support
+    /// All.
+    pub fn evict(&self, count: usize, policy: PersistedUriPermissionEvictionPolicy) -> crate::Result<Vec<FileUri>> {
+        on_android!({
+            let _state = self.lock();
+            let mut file = self.load()?;
+            let evicted = self.evict_in(&mut file, count, policy)?;
+            self.save(&file)?;
+            Ok(evicted)
+        })
+    }
+
+
+    /// Relinquishes a persisted URI permission according to the policy  
+    /// if the limit is reached and the permission of ***uri*** is not persisted yet,  
+    /// and then takes the permission by ***take***.
+    #[cfg(target_os = "android")]
+    pub(crate) fn take_with_eviction<T>(
+        &self,
+        uri: &FileUri,
+        take: impl FnOnce() -> crate::Result<T>,
+    ) -> crate::Result<T> {
+
+        let state = self.lock();
+
+        let target = grant_target(uri);
+        let already_persisted = self.0.get_all_persisted_uri_permissions()?
+            .any(|p| grant_target(p.uri()) == target);
+
+        if !already_persisted && self.0.get_persisted_uri_permission_capacity()?.remaining() == 0 {
+            let mut file = self.load()?;
+            let evicted = self.evict_in(&mut file, 1, state.eviction_policy)?;
+            self.save(&file)?;
+
+            if evicted.is_empty() {
+                return Err(crate::Error::PersistedUriPermissionLimitReached)
+            }
+        }
+
+        take()
+    }
+
+    fn evict_in(
+        &self,
+        file: &mut RegistryFile,
+        count: usize,
+        policy: PersistedUriPermissionEvictionPolicy,
+    ) -> crate::Result<Vec<FileUri>> {
+
+        use PersistedUriPermissionEvictionPolicy as Policy;
+
+        if count == 0 || policy == Policy::None {
+            return Ok(Vec::new())
+        }
+
+        let mut candidates = Vec::new();
+        for (permission, persisted_at) in self.0.get_all_persisted_uri_permissions_with_time()? {
+            let target = grant_target(permission.uri());
+            let entries = file.entries.iter().filter(|e| grant_target(&e.uri) == target).collect::<Vec<_>>();
+
+            if entries.iter().any(|e| e.pinned) {
+                continue
+            }
+
+            let time = match policy {
+                Policy::LeastRecentlyUsed => entries.iter()
+                    .map(|e| e.last_used_at.unwrap_or(e.registered_at))
+                    .max()
+                    .unwrap_or(persisted_at),
+                _ => persisted_at,
+            };
+
+            candidates.push((permission, time));
+        }
+
+        // Oldest first
+        candidates.sort_by_key(|c| c.1);
+
+        let mut evicted = Vec::new();
+        for (permission, _) in candidates.into_iter().take(count) {
+            self.0.release_persisted_uri_permission(permission.uri())?;
+
+            let target = grant_target(permission.uri());
+            for entry in file.entries.iter_mut().filter(|e| grant_target(&e.uri) == target) {
+                entry.status = RegisteredUriPermissionStatus::Revoked;
+                entry.validated_at = Some(std::time::SystemTime::now());
+            }
+
+            evicted.push(permission.uri().clone());
+        }
+
+        Ok(evicted)
+    }
+
+    #[cfg(target_os = "android")]
+    fn update(&self, key: &str, f: impl FnOnce(&mut RegisteredUriPermission)) -> crate::Result<bool> {
+        let _state = self.lock();
+        let mut file = self.load()?;
+
+        let Some(entry) = file.entries.iter_mut().find(|e| e.key == key) else {
+            return Ok(false)
+        };
+        f(entry);
+        self.save(&file)?;
+        Ok(true)
+    }
+
+    fn validate_entry(&self, entry: &mut RegisteredUriPermission, persisted: &[PersistedUriPermission]) {
+        let target = grant_target(&entry.uri);
+
+        entry.status = match persisted.iter().find(|p| grant_target(p.uri()) == target) {
+            None => RegisteredUriPermissionStatus::Revoked,
+            Some(p) => match self.exists(&entry.uri) {
+                Ok(true) => RegisteredUriPermissionStatus::Valid {
+                    can_read: p.can_read(),
+                    can_write: p.can_write(),
+                },
+                Ok(false) => RegisteredUriPermissionStatus::NotFound,
+                Err(_) => RegisteredUriPermissionStatus::Unchecked,
+            },
+        };
+        entry.validated_at = Some(std::time::SystemTime::now());
+    }
+
+    fn exists(&self, uri: &FileUri) -> crate::Result<bool> {
+        on_android!({
+            impl_se!(struct Req<'a> { uri: &'a FileUri });
+            impl_de!(struct Res { value: bool });
+
+            self.0.api
+                .run_mobile_plugin::<Res>("exists", Req { uri })
+                .map(|v| v.value)
+                .map_err(Into::into)
+        })
+    }
+
+    fn load(&self) -> crate::Result<RegistryFile> {
+        let path = self.resolve_file_path()?;
+
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RegistryFile { version: REGISTRY_VERSION, entries: Vec::new() }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, file: &RegistryFile) -> crate::Result<()> {
+        use std::io::Write as _;
+
+        // Replace atomically, so the registry is not corrupted even if the app is killed while writing.
+        let mut tmp_file = AtomicWriteFile::create(self.resolve_file_path()?)?;
+        tmp_file.write_all(&serde_json::to_vec(&file)?)?;
+        tmp_file.commit()?;
+        Ok(())
+    }
+
+    fn resolve_file_path(&self) -> crate::Result<std::path::PathBuf> {
+        self.0.private_storage().resolve_path_with(PrivateDir::Data, PERMISSION_REGISTRY_FILE_RELATIVE_PATH)
+    }
+
+    #[cfg(target_os = "android")]
+    fn lock(&self) -> std::sync::MutexGuard<'_, PermissionRegistryState> {
+        self.0.permission_registry_state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+const REGISTRY_VERSION: u32 = 1;
+
+/// Returns the URI that the permission is actually granted to.  
+/// The permissions of entries via [`AndroidFs::read_dir`] depend on the origin directory.
+fn grant_target(uri: &FileUri) -> &str {
+    uri.document_top_tree_uri.as_deref().unwrap_or(&uri.uri)
+}
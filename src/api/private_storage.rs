@@ -132,6 +132,30 @@ impl<'a, R: tauri::Runtime> PrivateStorage<'a, R> {
         })
     }
 
+    /// Same as [`PrivateStorage::write`], but with options.  
+    /// See [`WriteOptions`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn write_with(
+        &self, 
+        base_dir: PrivateDir, 
+        relative_path: impl AsRef<str>, 
+        contents: impl AsRef<[u8]>,
+        options: &WriteOptions,
+    ) -> crate::Result<()> {
+
+        on_android!({
+            let contents = contents.as_ref();
+
+            if options.ensure_space {
+                self.0.ensure_space(base_dir, contents.len() as u64)?;
+            }
+
+            self.write(base_dir, relative_path, contents)
+        })
+    }
+
     /// Open a file in read-only mode.  
     /// 
     /// If you only need to read the entire file contents, consider using [`PrivateStorage::read`]  or [`PrivateStorage::read_to_string`] instead.  
@@ -368,4 +392,94 @@ impl<'a, R: tauri::Runtime> PrivateStorage<'a, R> {
             Ok(std::fs::metadata(path)?)
         })
     }
-}
\ No newline at end of file
+
+    /// Creates a new temporary file in [`PrivateDir::Cache`] with a unique name.  
+    /// The file is removed when the returned [`TempFile`] is dropped.
+    /// 
+    /// This is safe to use from multiple threads, webviews and processes of this app.  
+    /// Temporary files left by terminated processes are removed when the plugin is initialized.
+    /// 
+    /// # Support
+    /// All.
+    pub fn create_temp_file(&self) -> crate::Result<TempFile<R>> {
+        on_android!({
+            self.create_temp_file_for("temp_file")
+        })
+    }
+
+    /// Creates a new temporary directory in [`PrivateDir::Cache`] with a unique name.  
+    /// The directory and its contents are removed when the returned [`TempDir`] is dropped.
+    /// 
+    /// This is safe to use from multiple threads, webviews and processes of this app.  
+    /// Temporary directories left by terminated processes are removed when the plugin is initialized.
+    /// 
+    /// # Support
+    /// All.
+    pub fn create_temp_dir(&self) -> crate::Result<TempDir> {
+        on_android!({
+            self.create_temp_dir_for("temp_dir")
+        })
+    }
+
+    /// Typed value persisted as a JSON file in [`PrivateDir::Data`].  
+    /// See [`Store`] for details.
+    /// 
+    /// # Args
+    /// - ***name*** :  
+    /// Name of the store, such as `settings`.  
+    /// This is used as the file name, so it cannot contain `/`.  
+    /// Stores with the same name share the same value.
+    pub fn store<T: serde::Serialize + serde::de::DeserializeOwned>(&self, name: impl Into<String>) -> Store<'a, R, T> {
+        Store::new(self.0, name.into())
+    }
+
+    /// API of encrypted files.  
+    /// This requires the `encryption` feature.  
+    /// See [`EncryptedStorage`] for details.
+    #[cfg(feature = "encryption")]
+    pub fn encrypted(&self) -> EncryptedStorage<'a, R> {
+        EncryptedStorage::new(self.0, None)
+    }
+
+    /// API of encrypted files with the key provided by ***key_provider*** instead of Android Keystore.  
+    /// This requires the `encryption` feature.  
+    /// See [`EncryptedStorage`] for details.
+    #[cfg(feature = "encryption")]
+    pub fn encrypted_with(&self, key_provider: impl KeyProvider + 'static) -> EncryptedStorage<'a, R> {
+        EncryptedStorage::new(self.0, Some(std::sync::Arc::new(key_provider)))
+    }
+
+    /// API of files compressed by ***compression***.  
+    /// This requires the `compression` feature.  
+    /// See [`CompressedStorage`] for details.
+    /// 
+    /// ***compression*** is used only for writing.  
+    /// When reading, the format is detected from the contents, and files that are not compressed are read as is.
+    /// 
+    /// This is separate from [`PrivateStorage::write`], [`PrivateStorage::read`] and [`PrivateStorage::open_file`],  
+    /// because they handle the file as is, so that it has the same contents as when accessed via std::fs by [`PrivateStorage::resolve_path`].  
+    /// Compressing there would silently change the files that the app or other libraries read by path.
+    #[cfg(feature = "compression")]
+    pub fn compressed(&self, compression: Compression) -> CompressedStorage<'a, R> {
+        CompressedStorage::new(self.0, compression)
+    }
+
+    /// API of managing the size of the files in [`PrivateDir::Cache`].
+    pub fn cache_manager(&self) -> CacheManager<'a, R> {
+        CacheManager(self.0)
+    }
+
+    /// Creates a temporary file whose name starts with ***prefix***, such as the name of the function using it.
+    #[cfg(target_os = "android")]
+    pub(crate) fn create_temp_file_for(&self, prefix: &str) -> crate::Result<TempFile<R>> {
+        let tmp_root = self.resolve_path_with(PrivateDir::Cache, TMP_DIR_RELATIVE_PATH)?;
+        crate::api::create_temp_file(self.0.app.clone(), &tmp_root, prefix)
+    }
+
+    /// Creates a temporary directory whose name starts with ***prefix***, such as the name of the function using it.
+    #[cfg(target_os = "android")]
+    pub(crate) fn create_temp_dir_for(&self, prefix: &str) -> crate::Result<TempDir> {
+        let tmp_root = self.resolve_path_with(PrivateDir::Cache, TMP_DIR_RELATIVE_PATH)?;
+        crate::api::create_temp_dir(&tmp_root, prefix)
+    }
+}
@@ -0,0 +1,444 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde::{de::DeserializeOwned, Serialize};
+use crate::*;
+
+
+/// Event emitted with the name of the store as payload when a [`Store`] is changed.
+pub const STORE_CHANGED_EVENT: &str = "android-fs://store-changed";
+
+/// Typed value persisted as a JSON file in [`PrivateDir::Data`].
+/// 
+/// This is obtained by [`PrivateStorage::store`].  
+/// The file is replaced atomically, so the value is not corrupted even if the app is killed while writing.
+/// 
+/// The schema of the value can be versioned by [`Store::version`],  
+/// and the value saved with an older version is converted by the callbacks registered by [`Store::migration`] when it is read.
+/// 
+/// # Examples
+/// ```no_run
+/// #[derive(Default, serde::Serialize, serde::Deserialize)]
+/// struct Settings {
+///     theme: String,
+///     font_size: u32,
+/// }
+///
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::AndroidFsExt;
+///
+///     let api = app.android_fs();
+///     let store = api.private_storage()
+///         .store::<Settings>("settings")
+///         .version(2)
+///         .migration(1, |mut value| {
+///             // Version 1 did not have font_size
+///             value["font_size"] = 14.into();
+///             Ok(value)
+///         });
+///
+///     let settings = store.get_or_default().unwrap();
+///     store.update(|s| s.theme = "dark".into()).unwrap();
+/// }
+/// ```
+pub struct Store<'a, R: tauri::Runtime, T> {
+    afs: &'a AndroidFs<R>,
+    name: String,
+    version: u32,
+    migrations: Vec<(u32, Migration<'a>)>,
+    _marker: std::marker::PhantomData<fn() -> T>,
+}
+
+#[derive(Default)]
+pub(crate) struct StoreState {
+    callbacks: std::collections::HashMap<String, Vec<ChangeCallback>>,
+}
+
+impl<'a, R: tauri::Runtime, T: Serialize + DeserializeOwned> Store<'a, R, T> {
+
+    pub(crate) fn new(afs: &'a AndroidFs<R>, name: String) -> Self {
+        Self {
+            afs,
+            name,
+            version: 1,
+            migrations: Vec::new(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets the current schema version of the value.  
+    /// The value is saved with this version.
+    /// 
+    /// By default, this is 1.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Registers a callback that converts the JSON value saved with the version ***from*** to the version ***from*** + 1.
+    /// 
+    /// When the saved version is older than [`Store::version`],  
+    /// the callbacks are applied in order of the version, and the result is saved.  
+    /// If any callback is missing, reading fails.
+    pub fn migration(
+        mut self,
+        from: u32,
+        migrate: impl Fn(serde_json::Value) -> crate::Result<serde_json::Value> + 'a,
+    ) -> Self {
+
+        self.migrations.retain(|(v, _)| *v != from);
+        self.migrations.push((from, Box::new(migrate)));
+        self
+    }
+
+    /// Name of this store.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the value.  
+    /// Returns None if the value has not been saved.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get(&self) -> crate::Result<Option<T>> {
+        on_android!({
+            let path = self.resolve_path()?;
+            let _state = self.lock_state();
+            self.load(&path)
+        })
+    }
+
+    /// Gets the value, or the default value if it has not been saved.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_or_default(&self) -> crate::Result<T> where T: Default {
+        on_android!({
+            self.get().map(Option::unwrap_or_default)
+        })
+    }
+
+    /// Saves the value.
+    /// 
+    /// # Support
+    /// All.
+    pub fn set(&self, value: &T) -> crate::Result<()> {
+        on_android!({
+            let path = self.resolve_path()?;
+            let json = serde_json::to_value(value)?;
+
+            let callbacks = {
+                let state = self.lock_state();
+                write_file(&path, &StoreFile { version: self.version, data: json.clone() })?;
+                state.callbacks.get(&self.name).cloned().unwrap_or_default()
+            };
+
+            self.notify(&callbacks, &json);
+            Ok(())
+        })
+    }
+
+    /// Modifies the value with ***f*** and saves it, and returns the new value.  
+    /// If the value has not been saved, the default value is passed to ***f***.
+    /// 
+    /// Reading and writing are done atomically against other calls to this store in this app process.
+    /// 
+    /// # Support
+    /// All.
+    pub fn update(&self, f: impl FnOnce(&mut T)) -> crate::Result<T> where T: Default {
+        on_android!({
+            let path = self.resolve_path()?;
+
+            let (value, json, callbacks) = {
+                let state = self.lock_state();
+                let mut value = self.load(&path)?.unwrap_or_default();
+                f(&mut value);
+
+                let json = serde_json::to_value(&value)?;
+                write_file(&path, &StoreFile { version: self.version, data: json.clone() })?;
+                (value, json, state.callbacks.get(&self.name).cloned().unwrap_or_default())
+            };
+
+            self.notify(&callbacks, &json);
+            Ok(value)
+        })
+    }
+
+    /// Removes the saved value.  
+    /// Callbacks registered by [`Store::on_change`] are not called.
+    /// 
+    /// # Support
+    /// All.
+    pub fn remove(&self) -> crate::Result<()> {
+        on_android!({
+            let path = self.resolve_path()?;
+            let _state = self.lock_state();
+
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    /// Registers a callback that is called with the new value each time it is saved by [`Store::set`] or [`Store::update`].  
+    /// This applies to all [`Store`] with the same name in this app process.
+    /// 
+    /// The event [`STORE_CHANGED_EVENT`] is also emitted to the frontend.
+    /// 
+    /// # Support
+    /// All.
+    pub fn on_change(&self, callback: impl Fn(&T) + Send + Sync + 'static) -> crate::Result<()> where T: 'static {
+        on_android!({
+            let callback = move |json: &serde_json::Value| {
+                if let Ok(value) = serde_json::from_value::<T>(json.clone()) {
+                    callback(&value);
+                }
+            };
+
+            self.lock_state()
+                .callbacks
+                .entry(self.name.clone())
+                .or_default()
+                .push(Arc::new(callback));
+
+            Ok(())
+        })
+    }
+
+    /// Gets the path of the file in which the value is saved.
+    /// 
+    /// # Support
+    /// All.
+    pub fn resolve_path(&self) -> crate::Result<PathBuf> {
+        on_android!({
+            validate_name(&self.name)?;
+
+            self.afs.private_storage().resolve_path_with(
+                PrivateDir::Data,
+                format!("{STORE_DIR_RELATIVE_PATH}/{}.json", self.name)
+            )
+        })
+    }
+
+
+    fn load(&self, path: &Path) -> crate::Result<Option<T>> {
+        load(path, self.version, &self.migrations)
+    }
+
+    #[cfg(target_os = "android")]
+    fn notify(&self, callbacks: &[ChangeCallback], json: &serde_json::Value) {
+        use tauri::Emitter as _;
+
+        for callback in callbacks {
+            callback(json);
+        }
+
+        let _ = self.afs.app.emit(STORE_CHANGED_EVENT, &self.name);
+    }
+
+    #[cfg(target_os = "android")]
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, StoreState> {
+        self.afs.store_state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+/// Callback registered by [`Store::on_change`], which receives the new value as JSON.
+type ChangeCallback = Arc<dyn Fn(&serde_json::Value) + Send + Sync>;
+
+/// Callback that converts the JSON value to the next version.
+type Migration<'a> = Box<dyn Fn(serde_json::Value) -> crate::Result<serde_json::Value> + 'a>;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoreFile {
+    version: u32,
+    data: serde_json::Value,
+}
+
+fn validate_name(name: &str) -> crate::Result<()> {
+    let is_valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\', '\0']);
+
+    if !is_valid {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid store name: {name:?}")
+        ).into())
+    }
+
+    Ok(())
+}
+
+/// Reads the value and converts it to ***version***.  
+/// If it was saved with an older version, the converted value is saved.
+fn load<T: DeserializeOwned>(
+    path: &Path,
+    version: u32,
+    migrations: &[(u32, Migration<'_>)],
+) -> crate::Result<Option<T>> {
+
+    let Some(file) = read_file(path)? else {
+        return Ok(None)
+    };
+
+    let saved_version = file.version;
+    let data = migrate(file, version, migrations)?;
+
+    if saved_version != version {
+        write_file(path, &StoreFile { version, data: data.clone() })?;
+    }
+
+    Ok(Some(serde_json::from_value(data)?))
+}
+
+fn read_file(path: &Path) -> crate::Result<Option<StoreFile>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes the file to a temporary file and renames it,  
+/// so that the file is either the old or the new contents even if the process is killed.
+fn write_file(path: &Path, file: &StoreFile) -> crate::Result<()> {
+    use std::io::Write as _;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("json.tmp");
+    {
+        let mut tmp_file = std::fs::File::create(&tmp_path)?;
+        tmp_file.write_all(&serde_json::to_vec(file)?)?;
+        tmp_file.sync_all()?;
+    }
+    std::fs::rename(&tmp_path, path)?;
+
+    // Persist the rename itself
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::File::open(parent).and_then(|d| d.sync_all());
+    }
+
+    Ok(())
+}
+
+/// Converts the data to ***version*** by applying ***migrations*** in order.
+fn migrate(
+    file: StoreFile,
+    version: u32,
+    migrations: &[(u32, Migration<'_>)],
+) -> crate::Result<serde_json::Value> {
+
+    if version < file.version {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("The store was saved with newer version {}, but the current version is {version}.", file.version)
+        ).into())
+    }
+
+    let mut data = file.data;
+
+    for from in file.version..version {
+        let Some((_, migrate)) = migrations.iter().find(|(v, _)| *v == from) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("No migration of the store from version {from} to {}.", from + 1)
+            ).into())
+        };
+
+        data = migrate(data)?;
+    }
+
+    Ok(data)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+    use serde_json::json;
+
+    fn migrations() -> Vec<(u32, Migration<'static>)> {
+        vec![
+            (1, Box::new(|mut v: serde_json::Value| { v["b"] = json!(2); Ok(v) }) as Migration),
+            (2, Box::new(|mut v: serde_json::Value| { v["c"] = json!(3); Ok(v) }) as Migration),
+        ]
+    }
+
+    fn save(path: &Path, version: u32, data: serde_json::Value) {
+        write_file(path, &StoreFile { version, data }).unwrap();
+    }
+
+    #[test]
+    fn chains_migrations_and_saves_result() {
+        let dir = TestDir::new("store-migrate");
+        let path = dir.join("a.json");
+        save(&path, 1, json!({ "a": 1 }));
+
+        let value = load::<serde_json::Value>(&path, 3, &migrations()).unwrap();
+        assert_eq!(value, Some(json!({ "a": 1, "b": 2, "c": 3 })));
+
+        let saved = read_file(&path).unwrap().unwrap();
+        assert_eq!(saved.version, 3);
+        assert_eq!(saved.data, json!({ "a": 1, "b": 2, "c": 3 }));
+    }
+
+    #[test]
+    fn fails_without_migration() {
+        let dir = TestDir::new("store-missing");
+        let path = dir.join("a.json");
+        save(&path, 1, json!({ "a": 1 }));
+
+        assert!(load::<serde_json::Value>(&path, 4, &migrations()).is_err());
+
+        // The saved value is kept as is.
+        assert_eq!(read_file(&path).unwrap().unwrap().version, 1);
+    }
+
+    #[test]
+    fn fails_with_newer_saved_version() {
+        let dir = TestDir::new("store-newer");
+        let path = dir.join("a.json");
+        save(&path, 3, json!({ "a": 1 }));
+
+        assert!(load::<serde_json::Value>(&path, 2, &migrations()).is_err());
+    }
+
+    #[test]
+    fn returns_none_if_not_saved() {
+        let dir = TestDir::new("store-none");
+
+        assert!(load::<serde_json::Value>(&dir.join("a.json"), 1, &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn replaces_file_without_leaving_temporary_file() {
+        let dir = TestDir::new("store-replace");
+        let path = dir.join("sub/a.json");
+        save(&path, 1, json!({ "a": 1 }));
+        save(&path, 1, json!({ "a": 2 }));
+
+        assert_eq!(read_file(&path).unwrap().unwrap().data, json!({ "a": 2 }));
+
+        let names = std::fs::read_dir(dir.join("sub")).unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a.json"]);
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        for name in ["", ".", "..", "a/b", "/a", "a\\b", "a\0b"] {
+            assert!(validate_name(name).is_err(), "{name:?}");
+        }
+        for name in ["a", "a.b", "..a"] {
+            assert!(validate_name(name).is_ok(), "{name:?}");
+        }
+    }
+}
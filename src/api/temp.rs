@@ -0,0 +1,460 @@
+use std::path::{Path, PathBuf};
+use crate::*;
+
+
+/// Temporary file created by [`PrivateStorage::create_temp_file`].
+/// 
+/// The file is removed when this is dropped.  
+/// To keep the result, use [`TempFile::persist`].
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle, dest: &tauri_plugin_android_fs::FileUri) {
+///     use std::io::Write as _;
+///     use tauri_plugin_android_fs::AndroidFsExt;
+///
+///     let api = app.android_fs();
+///     let mut tmp = api.private_storage().create_temp_file().unwrap();
+///
+///     tmp.as_file_mut().write_all(b"contents").unwrap();
+///     tmp.persist(dest).unwrap();
+/// }
+/// ```
+pub struct TempFile<R: tauri::Runtime> {
+    file: std::fs::File,
+    path: PathBuf,
+    app: tauri::AppHandle<R>,
+}
+
+impl<R: tauri::Runtime> TempFile<R> {
+
+    /// Path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// URI of the file.  
+    /// This can be used as the source of functions such as [`AndroidFs::copy_via_kotlin`],  
+    /// but cannot be passed to other apps.
+    pub fn uri(&self) -> FileUri {
+        (&self.path).into()
+    }
+
+    /// File opened in read and write mode.
+    pub fn as_file(&self) -> &std::fs::File {
+        &self.file
+    }
+
+    /// File opened in read and write mode.
+    pub fn as_file_mut(&mut self) -> &mut std::fs::File {
+        &mut self.file
+    }
+
+    /// Copies the contents to ***dest*** on Kotlin side by [`AndroidFs::copy_via_kotlin`], and removes this.  
+    /// Even if this fails, this is removed.
+    /// 
+    /// # Args
+    /// - ***dest*** :  
+    /// The URI of destination file, such as a content URI picked by dialogs.  
+    /// This needs to be **writable**.
+    /// 
+    /// # Support
+    /// All.
+    pub fn persist(self, dest: &FileUri) -> crate::Result<()> {
+        on_android!({
+            self.app.android_fs().copy_via_kotlin(&self.uri(), dest)
+        })
+    }
+}
+
+impl<R: tauri::Runtime> Drop for TempFile<R> {
+
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Temporary directory created by [`PrivateStorage::create_temp_dir`].
+/// 
+/// The directory and its contents are removed when this is dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+
+    /// Path of the directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// URI of the directory.
+    pub fn uri(&self) -> FileUri {
+        (&self.path).into()
+    }
+}
+
+impl Drop for TempDir {
+
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// File that is written to a temporary file next to the destination,  
+/// and replaces the destination only when [`AtomicWriteFile::commit`] is called.
+/// 
+/// If this is dropped without committing, the temporary file is removed and the destination is not changed.
+pub struct AtomicWriteFile {
+    file: std::fs::File,
+    tmp_path: PathBuf,
+    path: PathBuf,
+    committed: bool,
+}
+
+impl AtomicWriteFile {
+
+    /// Creates the temporary file for ***path***.  
+    /// Recursively create parent directories if they are missing.
+    /// 
+    /// The name of the temporary file contains the process ID and a counter,  
+    /// so that concurrent writers to the same destination do not share it.
+    pub(crate) fn create(path: PathBuf) -> std::io::Result<Self> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(format!(".tmp{}-{id}", std::process::id()));
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let file = std::fs::File::options().write(true).create_new(true).open(&tmp_path)?;
+        Ok(Self { file, tmp_path, path, committed: false })
+    }
+
+    /// Path of the destination.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Flushes the contents to the storage, and replaces the destination with this file.
+    pub fn commit(mut self) -> std::io::Result<()> {
+        self.file.sync_all()?;
+        std::fs::rename(&self.tmp_path, &self.path)?;
+        self.committed = true;
+
+        // Persist the rename itself
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::File::open(parent).and_then(|d| d.sync_all());
+        }
+
+        Ok(())
+    }
+}
+
+impl std::io::Write for AtomicWriteFile {
+
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for AtomicWriteFile {
+
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.tmp_path);
+        }
+    }
+}
+
+
+/// Creates a temporary file in the directory of this process under ***tmp_root***.
+pub(crate) fn create_temp_file<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    tmp_root: &Path,
+    prefix: &str,
+) -> crate::Result<TempFile<R>> {
+
+    create_unique(tmp_root, prefix, |path| {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map(|file| TempFile { file, path: path.to_path_buf(), app: app.clone() })
+    })
+}
+
+/// Creates a temporary directory in the directory of this process under ***tmp_root***.
+pub(crate) fn create_temp_dir(tmp_root: &Path, prefix: &str) -> crate::Result<TempDir> {
+    create_unique(tmp_root, prefix, |path| {
+        std::fs::create_dir(path).map(|_| TempDir { path: path.to_path_buf() })
+    })
+}
+
+/// Removes the temporary files except those of other running processes.  
+/// This is called once when the plugin is initialized.
+/// 
+/// Each process holds the lock of [`LOCK_FILE_NAME`] in its directory while running.  
+/// The lock is released by the OS even if the process is killed,  
+/// so a directory whose lock can be taken is no longer used.
+pub(crate) fn remove_stale_temp_files(tmp_root: &Path) {
+    let Ok(entries) = std::fs::read_dir(tmp_root) else {
+        return
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let _ = match entry.file_type() {
+            Ok(t) if t.is_dir() => remove_dir_if_unlocked(&path),
+            _ => std::fs::remove_file(&path),
+        };
+    }
+}
+
+/// Name of the file locked by the process that owns the directory.
+const LOCK_FILE_NAME: &str = "pluginAndroidFs-lock";
+
+/// Removes the directory of a process if its lock can be taken.
+fn remove_dir_if_unlocked(dir: &Path) -> std::io::Result<()> {
+    // The lock file is created if missing, so that it is shared with the owner that is about to lock it.
+    let file = open_lock_file(dir)?;
+
+    if lock(&file, false)? {
+        std::fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Returns the directory of this process under ***tmp_root***, and locks it for the lifetime of this process.
+fn process_dir(tmp_root: &Path) -> std::io::Result<PathBuf> {
+    static LOCKED: std::sync::Mutex<Vec<(PathBuf, std::fs::File)>> = std::sync::Mutex::new(Vec::new());
+
+    let dir = tmp_root.join(std::process::id().to_string());
+    let mut locked = LOCKED.lock().unwrap_or_else(|e| e.into_inner());
+
+    if locked.iter().any(|(d, _)| *d == dir) {
+        return Ok(dir)
+    }
+
+    loop {
+        std::fs::create_dir_all(&dir)?;
+
+        let file = match open_lock_file(&dir) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        // This waits while another process is removing the directory as stale.
+        lock(&file, true)?;
+
+        // If the directory was removed before it was locked, create it again.
+        if is_linked(&file, &dir.join(LOCK_FILE_NAME)) {
+            locked.push((dir.clone(), file));
+            return Ok(dir)
+        }
+    }
+}
+
+fn open_lock_file(dir: &Path) -> std::io::Result<std::fs::File> {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(LOCK_FILE_NAME))
+}
+
+/// Takes the exclusive lock of the file by `flock`, and returns false if it is held by another.  
+/// If ***wait*** is true, this waits until the lock is released instead.
+#[cfg(unix)]
+fn lock(file: &std::fs::File, wait: bool) -> std::io::Result<bool> {
+    use std::os::unix::io::AsRawFd as _;
+
+    let operation = match wait {
+        true => libc::LOCK_EX,
+        false => libc::LOCK_EX | libc::LOCK_NB,
+    };
+
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+            return Ok(true)
+        }
+
+        let e = std::io::Error::last_os_error();
+        match e.kind() {
+            std::io::ErrorKind::Interrupted => continue,
+            std::io::ErrorKind::WouldBlock => return Ok(false),
+            _ => return Err(e),
+        }
+    }
+}
+
+/// Processes cannot share the directory on this platform, so the lock is always taken.
+#[cfg(not(unix))]
+fn lock(_file: &std::fs::File, _wait: bool) -> std::io::Result<bool> {
+    Ok(true)
+}
+
+/// Returns true if the opened file is still at ***path***.
+#[cfg(unix)]
+fn is_linked(file: &std::fs::File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt as _;
+
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_linked(_file: &std::fs::File, path: &Path) -> bool {
+    path.exists()
+}
+
+/// Calls ***create*** with a unique path, and retries if it already exists.  
+/// Files are separated by process ID, so that processes sharing this app do not conflict.
+fn create_unique<T>(
+    tmp_root: &Path,
+    prefix: &str,
+    create: impl Fn(&Path) -> std::io::Result<T>,
+) -> crate::Result<T> {
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = process_dir(tmp_root)?;
+
+    loop {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("{prefix} {nanos}-{id}"));
+
+        match create(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            result => return result.map_err(Into::into),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+
+    #[test]
+    fn keeps_directory_of_this_process() {
+        let root = TestDir::new("temp-own");
+        let tmp = create_temp_dir(root.path(), "test").unwrap();
+
+        remove_stale_temp_files(root.path());
+
+        assert!(tmp.path().exists());
+    }
+
+    #[test]
+    fn removes_unlocked_directory_and_stray_files() {
+        let root = TestDir::new("temp-stale");
+        let stale = root.join("1");
+        std::fs::create_dir_all(stale.join("a")).unwrap();
+        std::fs::write(stale.join(LOCK_FILE_NAME), b"").unwrap();
+        std::fs::write(root.join("stray"), b"").unwrap();
+
+        // Directories created without the lock file are also removed.
+        let legacy = root.join("2");
+        std::fs::create_dir_all(&legacy).unwrap();
+
+        remove_stale_temp_files(root.path());
+
+        assert!(!stale.exists());
+        assert!(!legacy.exists());
+        assert!(!root.join("stray").exists());
+    }
+
+    #[test]
+    fn keeps_locked_directory() {
+        let root = TestDir::new("temp-locked");
+        let other = root.join("1");
+        std::fs::create_dir_all(&other).unwrap();
+
+        // Each open file description has its own lock, same as another process.
+        let file = open_lock_file(&other).unwrap();
+        assert!(lock(&file, false).unwrap());
+
+        remove_stale_temp_files(root.path());
+        assert!(other.exists());
+
+        drop(file);
+        remove_stale_temp_files(root.path());
+        assert!(!other.exists());
+    }
+
+    #[test]
+    fn atomic_write_file_replaces_only_on_commit() {
+        use std::io::Write as _;
+
+        let dir = TestDir::new("temp-atomic");
+        let path = dir.join("sub/a.txt");
+
+        let mut file = AtomicWriteFile::create(path.clone()).unwrap();
+        file.write_all(b"new").unwrap();
+        drop(file);
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_dir(dir.join("sub")).unwrap().count(), 0);
+
+        std::fs::write(&path, b"old").unwrap();
+        let mut file = AtomicWriteFile::create(path.clone()).unwrap();
+        file.write_all(b"new").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"old");
+
+        file.commit().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        assert_eq!(std::fs::read_dir(dir.join("sub")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn atomic_write_files_to_same_path_do_not_interfere() {
+        use std::io::Write as _;
+
+        let dir = TestDir::new("temp-atomic-concurrent");
+        let path = dir.join("a.txt");
+
+        let mut first = AtomicWriteFile::create(path.clone()).unwrap();
+        let mut second = AtomicWriteFile::create(path.clone()).unwrap();
+        first.write_all(b"first").unwrap();
+        second.write_all(b"second").unwrap();
+
+        second.commit().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        first.commit().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"first");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn locks_and_reuses_directory_of_this_process() {
+        let root = TestDir::new("temp-reuse");
+        let dir = process_dir(root.path()).unwrap();
+
+        assert_eq!(process_dir(root.path()).unwrap(), dir);
+        assert!(!lock(&open_lock_file(&dir).unwrap(), false).unwrap());
+    }
+}
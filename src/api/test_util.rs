@@ -0,0 +1,39 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+
+/// Directory under the system temporary directory, removed when dropped.
+pub(crate) struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+
+    pub(crate) fn new(name: &str) -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("pluginAndroidFs-test-{name}-{}-{id}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        // Resolve symlinks such as /tmp on macOS, so that paths from inotify and the like match.
+        Self { path: path.canonicalize().unwrap() }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path.join(path)
+    }
+}
+
+impl Drop for TestDir {
+
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,440 @@
+use crate::*;
+
+
+/// API of the thumbnail cache stored in [`PrivateDir::Cache`].
+/// 
+/// Entries are keyed by the URI, its last modified time, the requested size and the format.  
+/// So if the file is modified, the thumbnail is regenerated automatically.  
+/// When the total size exceeds [`ThumbnailCacheLimit`], the least recently used entries are removed.
+/// 
+/// # Examples
+/// ```
+/// fn example(app: &tauri::AppHandle) {
+///     use tauri_plugin_android_fs::AndroidFsExt;
+///
+///     let api = app.android_fs();
+///     let thumbnail_cache = api.thumbnail_cache();
+/// }
+/// ```
+pub struct ThumbnailCache<'a, R: tauri::Runtime>(pub(crate) &'a AndroidFs<R>);
+
+#[derive(Default)]
+pub(crate) struct ThumbnailCacheState {
+    limit: ThumbnailCacheLimit,
+
+    /// Total bytes and number of entries. `None` if not counted yet.
+    usage: Option<(u64, usize)>,
+}
+
+impl<'a, R: tauri::Runtime> ThumbnailCache<'a, R> {
+
+    /// Query the provider to get a file thumbnail, using the cache if available.  
+    /// If thumbnail does not exist it, return None.
+    /// 
+    /// See [`AndroidFs::get_thumbnail`] for details of the args.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_thumbnail(
+        &self,
+        uri: &FileUri,
+        preferred_size: Size,
+        format: ImageFormat,
+    ) -> crate::Result<Option<Vec<u8>>> {
+
+        on_android!({
+            match self.get_thumbnail_path(uri, preferred_size, format)? {
+                Some(path) => Ok(Some(std::fs::read(path)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// Same as [`ThumbnailCache::get_thumbnail`],  
+    /// but returns the absolute path of the cached thumbnail instead of its contents.
+    /// 
+    /// The returned file may be removed by eviction or the system at any time,  
+    /// so do not keep the path for long.
+    /// 
+    /// # Support
+    /// All.
+    pub fn get_thumbnail_path(
+        &self,
+        uri: &FileUri,
+        preferred_size: Size,
+        format: ImageFormat,
+    ) -> crate::Result<Option<std::path::PathBuf>> {
+
+        on_android!({
+            let dir = self.resolve_dir()?;
+            let path = dir.join(self.entry_name(uri, preferred_size, &format)?);
+
+            if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+                // Mark as recently used
+                let _ = file.set_modified(std::time::SystemTime::now());
+                return Ok(Some(path))
+            }
+
+            std::fs::create_dir_all(&dir)?;
+
+            let tmp_path = {
+                use std::sync::atomic::{AtomicUsize, Ordering};
+
+                static COUNTER: AtomicUsize = AtomicUsize::new(0);
+                let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+                dir.join(format!("{}{TMP_SUFFIX}{}-{id}", path.file_name().unwrap().to_string_lossy(), std::process::id()))
+            };
+
+            std::fs::File::create(&tmp_path)?;
+
+            let result = self.0.get_thumbnail_to(uri, &(&tmp_path).into(), preferred_size, format)
+                .and_then(|ok| {
+                    if !ok {
+                        return Ok(None)
+                    }
+
+                    std::fs::rename(&tmp_path, &path)?;
+                    Ok(Some(std::fs::metadata(&path)?.len()))
+                });
+
+            let _ = std::fs::remove_file(&tmp_path);
+
+            match result? {
+                Some(len) => {
+                    self.on_inserted(&dir, len);
+                    Ok(Some(path))
+                },
+                None => Ok(None),
+            }
+        })
+    }
+
+    /// Generates and caches thumbnails of the specified files in advance.  
+    /// Entries already cached are only marked as recently used.
+    /// 
+    /// Failures for each file are ignored.
+    /// 
+    /// # Support
+    /// All.
+    pub fn prewarm(
+        &self,
+        uris: &[FileUri],
+        preferred_size: Size,
+        format: ImageFormat,
+    ) -> crate::Result<()> {
+
+        on_android!({
+            for uri in uris {
+                let _ = self.get_thumbnail_path(uri, preferred_size, format.clone());
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes all cached thumbnails of the specified files, regardless of size and format.
+    /// 
+    /// # Support
+    /// All.
+    pub fn invalidate(&self, uris: &[FileUri]) -> crate::Result<()> {
+        on_android!({
+            let dir = self.resolve_dir()?;
+            let prefixes = uris.iter()
+                .map(|uri| format!("{:016x}-", fnv1a64(uri.uri.as_bytes())))
+                .collect::<Vec<_>>();
+
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+
+                if prefixes.iter().any(|p| name.starts_with(p)) {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+
+            self.lock_state().usage = None;
+            Ok(())
+        })
+    }
+
+    /// Removes all cached thumbnails.
+    /// 
+    /// # Support
+    /// All.
+    pub fn clear(&self) -> crate::Result<()> {
+        on_android!({
+            let dir = self.resolve_dir()?;
+
+            match std::fs::remove_dir_all(&dir) {
+                Ok(_) => {},
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+                Err(e) => return Err(e.into()),
+            }
+
+            self.lock_state().usage = None;
+            Ok(())
+        })
+    }
+
+    /// Sets the size limits of this cache.  
+    /// If the current entries exceed the new limits, the least recently used entries are removed immediately.
+    /// 
+    /// This is not persisted, so please set it each time the app starts.  
+    /// By default, [`ThumbnailCacheLimit::default`] is used.
+    /// 
+    /// # Support
+    /// All.
+    pub fn set_limit(&self, limit: ThumbnailCacheLimit) -> crate::Result<()> {
+        on_android!({
+            let dir = self.resolve_dir()?;
+            let mut state = self.lock_state();
+
+            state.limit = limit;
+            state.usage = Some(evict(&dir, &limit));
+            Ok(())
+        })
+    }
+
+    /// Gets the size limits of this cache.
+    /// 
+    /// # Support
+    /// All.
+    pub fn limit(&self) -> crate::Result<ThumbnailCacheLimit> {
+        on_android!({
+            Ok(self.lock_state().limit)
+        })
+    }
+
+    /// Gets the total bytes of the cached thumbnails.
+    /// 
+    /// # Support
+    /// All.
+    pub fn total_bytes(&self) -> crate::Result<u64> {
+        on_android!({
+            let dir = self.resolve_dir()?;
+            let mut state = self.lock_state();
+
+            let usage = match state.usage {
+                Some(usage) => usage,
+                None => evict(&dir, &state.limit),
+            };
+
+            state.usage = Some(usage);
+            Ok(usage.0)
+        })
+    }
+
+
+    fn resolve_dir(&self) -> crate::Result<std::path::PathBuf> {
+        self.0.private_storage().resolve_path_with(PrivateDir::Cache, THUMBNAIL_CACHE_DIR_RELATIVE_PATH)
+    }
+
+    fn entry_name(&self, uri: &FileUri, size: Size, format: &ImageFormat) -> crate::Result<String> {
+        let metadata = self.0.get_metadata(uri)?;
+        let last_modified = metadata.modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        let (format, quality) = format.to_plugin_args();
+        let key = format!(
+            "{last_modified}:{}:{}x{}:{format}:{quality}",
+            metadata.len(),
+            size.width,
+            size.height
+        );
+
+        Ok(format!("{:016x}-{:016x}", fnv1a64(uri.uri.as_bytes()), fnv1a64(key.as_bytes())))
+    }
+
+    #[cfg(target_os = "android")]
+    fn on_inserted(&self, dir: &std::path::Path, len: u64) {
+        let mut state = self.lock_state();
+        let limit = state.limit;
+
+        let usage = match state.usage {
+            Some((bytes, count)) if bytes + len <= limit.max_bytes && count < limit.max_entries => {
+                (bytes + len, count + 1)
+            },
+            _ => evict(dir, &limit),
+        };
+
+        state.usage = Some(usage);
+    }
+
+    #[cfg(target_os = "android")]
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, ThumbnailCacheState> {
+        self.0.thumbnail_cache_state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+const TMP_SUFFIX: &str = ".tmp";
+
+/// Time after which temporary files of other processes are considered to be left by a crash.
+const STALE_TMP_AGE: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Removes the least recently used entries until the limits are satisfied,  
+/// and returns the total bytes and the number of the remaining entries.  
+/// Temporary files left by crashed processes are also removed.
+fn evict(dir: &std::path::Path, limit: &ThumbnailCacheLimit) -> (u64, usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0)
+    };
+
+    let mut entries = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            let Some((_, owner)) = name.split_once(TMP_SUFFIX) else {
+                return true
+            };
+
+            if is_stale_tmp(owner, e) {
+                let _ = std::fs::remove_file(e.path());
+            }
+            false
+        })
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+            metadata.is_file().then(|| (e.path(), metadata.len(), modified))
+        })
+        .collect::<Vec<_>>();
+
+    // Newest first
+    entries.sort_by_key(|e| std::cmp::Reverse(e.2));
+
+    let mut bytes = 0;
+    let mut count = 0;
+
+    for (path, len, _) in entries {
+        if bytes + len <= limit.max_bytes && count < limit.max_entries {
+            bytes += len;
+            count += 1;
+        }
+        else {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    (bytes, count)
+}
+
+/// Returns whether the temporary file is not written by this process and has not been modified for a while.  
+/// ***owner*** is the part of the name after [`TMP_SUFFIX`], that is `<pid>-<id>`.
+fn is_stale_tmp(owner: &str, entry: &std::fs::DirEntry) -> bool {
+    let pid = owner.split_once('-').and_then(|(pid, _)| pid.parse::<u32>().ok());
+    if pid == Some(std::process::id()) {
+        return false
+    }
+
+    entry.metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .map_or(true, |age| STALE_TMP_AGE < age)
+}
+
+/// FNV-1a.  
+/// This is used instead of `std::hash` because the result must be stable across builds.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+    use std::time::{Duration, SystemTime};
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    /// Creates a file of ***len*** bytes modified ***age*** ago.
+    fn create(dir: &TestDir, name: &str, len: usize, age: Duration) {
+        let path = dir.join(name);
+        std::fs::write(&path, vec![0; len]).unwrap();
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    #[test]
+    fn removes_least_recently_used_by_bytes() {
+        let dir = TestDir::new("thumbnail-bytes");
+        create(&dir, "new", 10, HOUR);
+        create(&dir, "mid", 10, 2 * HOUR);
+        create(&dir, "old", 10, 3 * HOUR);
+
+        let limit = ThumbnailCacheLimit { max_bytes: 25, max_entries: 100 };
+
+        assert_eq!(evict(dir.path(), &limit), (20, 2));
+        assert!(dir.join("new").exists());
+        assert!(dir.join("mid").exists());
+        assert!(!dir.join("old").exists());
+    }
+
+    #[test]
+    fn removes_least_recently_used_by_entries() {
+        let dir = TestDir::new("thumbnail-entries");
+        create(&dir, "new", 10, HOUR);
+        create(&dir, "mid", 10, 2 * HOUR);
+        create(&dir, "old", 10, 3 * HOUR);
+
+        let limit = ThumbnailCacheLimit { max_bytes: 1000, max_entries: 1 };
+
+        assert_eq!(evict(dir.path(), &limit), (10, 1));
+        assert!(dir.join("new").exists());
+        assert!(!dir.join("mid").exists());
+        assert!(!dir.join("old").exists());
+    }
+
+    #[test]
+    fn removes_only_stale_tmp_files_of_other_processes() {
+        let dir = TestDir::new("thumbnail-tmp");
+        let this = std::process::id();
+        let other = this.wrapping_add(1);
+
+        create(&dir, &format!("stale{TMP_SUFFIX}{other}-0"), 10, HOUR);
+        create(&dir, &format!("writing{TMP_SUFFIX}{other}-1"), 10, Duration::ZERO);
+        create(&dir, &format!("own{TMP_SUFFIX}{this}-2"), 10, HOUR);
+        create(&dir, "entry", 10, HOUR);
+
+        let limit = ThumbnailCacheLimit { max_bytes: 1000, max_entries: 100 };
+
+        // Temporary files are not counted as entries
+        assert_eq!(evict(dir.path(), &limit), (10, 1));
+        assert!(!dir.join(format!("stale{TMP_SUFFIX}{other}-0")).exists());
+        assert!(dir.join(format!("writing{TMP_SUFFIX}{other}-1")).exists());
+        assert!(dir.join(format!("own{TMP_SUFFIX}{this}-2")).exists());
+        assert!(dir.join("entry").exists());
+    }
+
+    #[test]
+    fn returns_zero_for_missing_directory() {
+        let dir = TestDir::new("thumbnail-missing");
+
+        assert_eq!(evict(&dir.join("missing"), &ThumbnailCacheLimit::default()), (0, 0));
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(fnv1a64(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a64(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a64(b"foobar"), 0x85944171f73967e8);
+    }
+}
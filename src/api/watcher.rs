@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use crate::*;
+
+
+/// Event emitted with [`FileChange`] as payload when a watched file or directory is changed.
+pub const FILE_CHANGE_EVENT: &str = "android-fs://file-change";
+
+/// Interval to coalesce successive changes of the same file into one.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Handle of a watch started by [`AndroidFs::watch`].
+/// 
+/// Watching continues until this is dropped or [`WatchHandle::unwatch`] is called.
+pub struct WatchHandle<R: tauri::Runtime> {
+    id: u64,
+    app: tauri::AppHandle<R>,
+}
+
+impl<R: tauri::Runtime> WatchHandle<R> {
+
+    /// ID of this watch.  
+    /// This is same as [`FileChange::watch_id`] of the changes of this watch.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Registers a callback that is called with the changes of this watch.
+    /// 
+    /// The event [`FILE_CHANGE_EVENT`] is also emitted to the frontend.
+    pub fn on_change(&self, callback: impl Fn(&FileChange) + Send + Sync + 'static) {
+        #[cfg(target_os = "android")] {
+            use tauri::Manager as _;
+
+            let afs = self.app.state::<AndroidFs<R>>();
+            let mut state = lock(&afs);
+            if let Some(watch) = state.watches.get_mut(&self.id) {
+                watch.callbacks.push(Arc::new(callback));
+            }
+        }
+    }
+
+    /// Stops watching.  
+    /// This is same as dropping this.
+    pub fn unwatch(self) {}
+}
+
+impl<R: tauri::Runtime> Drop for WatchHandle<R> {
+
+    fn drop(&mut self) {
+        #[cfg(target_os = "android")] {
+            use tauri::Manager as _;
+
+            let afs = self.app.state::<AndroidFs<R>>();
+            let watch = lock(&afs).watches.remove(&self.id);
+
+            if let Some(Watch { source: WatchSource::Content(_), .. }) = watch {
+                impl_se!(struct Req { id: u64 });
+                impl_de!(struct Res;);
+
+                let _ = afs.api.run_mobile_plugin::<Res>("unwatch", Req { id: self.id });
+            }
+        }
+    }
+}
+
+
+#[derive(Default)]
+pub(crate) struct WatcherState {
+    next_id: u64,
+    watches: HashMap<u64, Watch>,
+}
+
+struct Watch {
+    callbacks: Vec<ChangeCallback>,
+    source: WatchSource,
+}
+
+/// Callback registered by [`WatchHandle::on_change`].
+type ChangeCallback = Arc<dyn Fn(&FileChange) + Send + Sync>;
+
+enum WatchSource {
+    Path(PathWatcher),
+    Content(Debouncer),
+}
+
+/// Starts watching the file or directory.  
+/// `file://` URIs are watched by inotify, and content URIs are watched by `ContentObserver`.
+#[cfg(target_os = "android")]
+pub(crate) fn watch<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    uri: &FileUri,
+    recursive: bool,
+) -> crate::Result<WatchHandle<R>> {
+
+    let id = {
+        let mut state = lock(afs);
+        state.next_id += 1;
+        state.next_id
+    };
+
+    let debouncer = {
+        let app = afs.app.clone();
+        Debouncer::new(DEBOUNCE_INTERVAL, move |changes| dispatch(&app, id, changes))
+    };
+
+    let source = match to_path(uri) {
+        Some(path) => WatchSource::Path(PathWatcher::new(&path, recursive, debouncer)?),
+        None => {
+            // Register the debouncer before the observer, so that no changes are lost.
+            lock(afs).watches.insert(id, Watch { callbacks: Vec::new(), source: WatchSource::Content(debouncer) });
+
+            if let Err(e) = watch_content(afs, id, uri, recursive) {
+                lock(afs).watches.remove(&id);
+                return Err(e)
+            }
+
+            return Ok(WatchHandle { id, app: afs.app.clone() })
+        },
+    };
+
+    lock(afs).watches.insert(id, Watch { callbacks: Vec::new(), source });
+    Ok(WatchHandle { id, app: afs.app.clone() })
+}
+
+#[cfg(target_os = "android")]
+fn watch_content<R: tauri::Runtime>(
+    afs: &AndroidFs<R>,
+    id: u64,
+    uri: &FileUri,
+    recursive: bool,
+) -> crate::Result<()> {
+
+    use tauri::Manager as _;
+
+    impl_se!(struct Req<'a> { id: u64, uri: &'a FileUri, recursive: bool, channel: tauri::ipc::Channel });
+    impl_de!(struct Res;);
+    impl_de!(struct Msg { uri: FileUri, kind: FileChangeKind });
+
+    let app = afs.app.clone();
+    let channel = tauri::ipc::Channel::new(move |body| {
+        if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+            if let Ok(msg) = serde_json::from_str::<Msg>(&json) {
+                let afs = app.state::<AndroidFs<R>>();
+                let state = lock(&afs);
+                if let Some(Watch { source: WatchSource::Content(debouncer), .. }) = state.watches.get(&id) {
+                    debouncer.push(msg.uri, msg.kind);
+                }
+            }
+        }
+        Ok(())
+    });
+
+    afs.api
+        .run_mobile_plugin::<Res>("watch", Req { id, uri, recursive, channel })
+        .map(|_| ())
+        .map_err(Into::into)
+}
+
+#[cfg(target_os = "android")]
+fn dispatch<R: tauri::Runtime>(app: &tauri::AppHandle<R>, id: u64, changes: Vec<(FileUri, FileChangeKind)>) {
+    use tauri::{Emitter as _, Manager as _};
+
+    let callbacks = match lock(&app.state::<AndroidFs<R>>()).watches.get(&id) {
+        Some(watch) => watch.callbacks.clone(),
+        None => return,
+    };
+
+    for (uri, kind) in changes {
+        let change = FileChange { watch_id: id, uri, kind };
+
+        for callback in &callbacks {
+            callback(&change);
+        }
+
+        let _ = app.emit(FILE_CHANGE_EVENT, &change);
+    }
+}
+
+#[cfg(target_os = "android")]
+fn lock<R: tauri::Runtime>(afs: &AndroidFs<R>) -> std::sync::MutexGuard<'_, WatcherState> {
+    afs.watcher_state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+
+// Watching by path below does not depend on Android, so it also works on Linux.
+
+/// Returns the path if the URI is `file://`.
+fn to_path(uri: &FileUri) -> Option<PathBuf> {
+    uri.uri.strip_prefix("file://").map(PathBuf::from)
+}
+
+/// Watcher of a file or directory by its path, using inotify on Android and Linux.
+struct PathWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl PathWatcher {
+
+    fn new(path: &Path, recursive: bool, debouncer: Debouncer) -> crate::Result<Self> {
+        use notify::Watcher as _;
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                for (path, kind) in to_changes(event) {
+                    debouncer.push(FileUri::from(path), kind);
+                }
+            }
+        }).map_err(to_error)?;
+
+        let mode = match recursive {
+            true => notify::RecursiveMode::Recursive,
+            false => notify::RecursiveMode::NonRecursive,
+        };
+
+        watcher.watch(path, mode).map_err(to_error)?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn to_changes(event: notify::Event) -> Vec<(PathBuf, FileChangeKind)> {
+    use notify::EventKind;
+    use notify::event::{ModifyKind, MetadataKind, RenameMode};
+
+    let kind = match event.kind {
+        EventKind::Create(_) => FileChangeKind::Create,
+        EventKind::Remove(_) => FileChangeKind::Remove,
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => FileChangeKind::Remove,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => FileChangeKind::Create,
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            let mut paths = event.paths.into_iter();
+            return paths.next().map(|p| (p, FileChangeKind::Remove))
+                .into_iter()
+                .chain(paths.next().map(|p| (p, FileChangeKind::Create)))
+                .collect()
+        },
+        EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime)) => return Vec::new(),
+        EventKind::Modify(_) => FileChangeKind::Modify,
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => return Vec::new(),
+    };
+
+    event.paths.into_iter().map(|p| (p, kind)).collect()
+}
+
+fn to_error(e: notify::Error) -> crate::Error {
+    match e.kind {
+        notify::ErrorKind::Io(e) => e.into(),
+        notify::ErrorKind::PathNotFound => std::io::Error::from(std::io::ErrorKind::NotFound).into(),
+        notify::ErrorKind::Generic(message) => std::io::Error::other(message).into(),
+        kind => std::io::Error::other(format!("{kind:?}")).into(),
+    }
+}
+
+/// Coalesces changes received within the interval, and passes them to the callback on another thread.  
+/// The thread ends when this is dropped.
+struct Debouncer {
+    tx: mpsc::Sender<(FileUri, FileChangeKind)>,
+}
+
+impl Debouncer {
+
+    fn new(
+        interval: Duration,
+        on_flush: impl Fn(Vec<(FileUri, FileChangeKind)>) + Send + 'static,
+    ) -> Self {
+
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut pending = Vec::new();
+            let mut deadline: Option<Instant> = None;
+
+            loop {
+                let received = match deadline {
+                    Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                    None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+                };
+
+                match received {
+                    Ok((uri, kind)) => {
+                        merge(&mut pending, uri, kind);
+                        deadline.get_or_insert_with(|| Instant::now() + interval);
+                    },
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        deadline = None;
+                        if !pending.is_empty() {
+                            on_flush(std::mem::take(&mut pending));
+                        }
+                    },
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        Self { tx }
+    }
+
+    fn push(&self, uri: FileUri, kind: FileChangeKind) {
+        let _ = self.tx.send((uri, kind));
+    }
+}
+
+/// Merges the change into the pending changes of the same URI, keeping the order of first occurrence.
+fn merge(pending: &mut Vec<(FileUri, FileChangeKind)>, uri: FileUri, kind: FileChangeKind) {
+    use FileChangeKind::*;
+
+    let Some(i) = pending.iter().position(|(u, _)| *u == uri) else {
+        pending.push((uri, kind));
+        return
+    };
+
+    match (pending[i].1, kind) {
+        // Created and removed within the interval, so nothing has changed.
+        (Create, Remove) => { pending.remove(i); },
+        (Create, _) => {},
+        (Remove, Create) => pending[i].1 = Modify,
+        (_, kind) => pending[i].1 = kind,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_util::TestDir;
+    use FileChangeKind::*;
+
+    fn uri(name: &str) -> FileUri {
+        FileUri::from(PathBuf::from(format!("/dir/{name}")))
+    }
+
+    fn merged(changes: &[(&str, FileChangeKind)]) -> Vec<(FileUri, FileChangeKind)> {
+        let mut pending = Vec::new();
+        for (name, kind) in changes {
+            merge(&mut pending, uri(name), *kind);
+        }
+        pending
+    }
+
+    #[test]
+    fn merge_coalesces_changes_of_same_uri() {
+        assert_eq!(merged(&[("a", Modify), ("a", Modify)]), vec![(uri("a"), Modify)]);
+        assert_eq!(merged(&[("a", Create), ("a", Modify)]), vec![(uri("a"), Create)]);
+        assert_eq!(merged(&[("a", Create), ("a", Remove)]), vec![]);
+        assert_eq!(merged(&[("a", Remove), ("a", Create)]), vec![(uri("a"), Modify)]);
+        assert_eq!(merged(&[("a", Modify), ("a", Remove)]), vec![(uri("a"), Remove)]);
+    }
+
+    #[test]
+    fn merge_keeps_order_of_first_occurrence() {
+        assert_eq!(
+            merged(&[("a", Modify), ("b", Create), ("a", Remove)]),
+            vec![(uri("a"), Remove), (uri("b"), Create)],
+        );
+    }
+
+    #[test]
+    fn to_path_accepts_only_file_uri() {
+        assert_eq!(to_path(&uri("a")), Some(PathBuf::from("/dir/a")));
+        assert_eq!(to_path(&FileUri { uri: "content://a/b".into(), document_top_tree_uri: None }), None);
+    }
+
+    #[test]
+    fn to_changes_splits_rename() {
+        use notify::event::{EventKind, ModifyKind, RenameMode};
+
+        let event = notify::Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(PathBuf::from("/dir/a"))
+            .add_path(PathBuf::from("/dir/b"));
+
+        assert_eq!(
+            to_changes(event),
+            vec![(PathBuf::from("/dir/a"), Remove), (PathBuf::from("/dir/b"), Create)],
+        );
+    }
+
+    #[test]
+    fn debouncer_flushes_coalesced_changes_once() {
+        let (tx, rx) = mpsc::channel();
+        let debouncer = Debouncer::new(Duration::from_millis(50), move |changes| {
+            let _ = tx.send(changes);
+        });
+
+        debouncer.push(uri("a"), Create);
+        debouncer.push(uri("a"), Modify);
+        debouncer.push(uri("b"), Modify);
+
+        let changes = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(changes, vec![(uri("a"), Create), (uri("b"), Modify)]);
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn path_watcher_reports_created_file() {
+        let dir = TestDir::new("watcher");
+        let (tx, rx) = mpsc::channel();
+        let debouncer = Debouncer::new(Duration::from_millis(50), move |changes| {
+            let _ = tx.send(changes);
+        });
+
+        let _watcher = PathWatcher::new(dir.path(), false, debouncer).unwrap();
+        std::fs::write(dir.join("a.txt"), b"a").unwrap();
+
+        let changes = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(changes.contains(&(FileUri::from(dir.join("a.txt")), Create)), "{changes:?}");
+    }
+
+    #[test]
+    fn path_watcher_fails_for_missing_path() {
+        let dir = TestDir::new("watcher-missing");
+        let debouncer = Debouncer::new(DEBOUNCE_INTERVAL, |_| {});
+
+        assert!(PathWatcher::new(&dir.join("missing"), false, debouncer).is_err());
+    }
+}
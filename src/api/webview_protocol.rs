@@ -0,0 +1,303 @@
+use std::io::{Read as _, Seek as _};
+use crate::*;
+
+
+/// API of the custom URI scheme protocol that serves files to the webview.
+/// 
+/// This requires the `protocol` feature.  
+/// Only files exposed by [`WebviewProtocol::expose`] are served,  
+/// so the webview cannot access other files via this protocol.  
+/// HTTP Range requests are supported, so this can be used as the source of `<video>` and `<audio>`.  
+/// Each response is limited to 8 MiB so that large files are not loaded into memory at once.  
+/// Files larger than that are served partially with `206 Partial Content` even if the request has no Range header,  
+/// so they can be used only by media elements that request the rest, not by `<img>` or `fetch`.
+/// 
+/// # Examples
+/// ```no_run
+/// fn example(app: &tauri::AppHandle, uri: &tauri_plugin_android_fs::FileUri) {
+///     use tauri_plugin_android_fs::AndroidFsExt;
+///
+///     let api = app.android_fs();
+///     let url = api.webview_protocol().expose(uri).unwrap();
+///
+///     // Use url as src of <img> or <video> on the frontend.
+/// }
+/// ```
+pub struct WebviewProtocol<'a, R: tauri::Runtime>(pub(crate) &'a AndroidFs<R>);
+
+impl<'a, R: tauri::Runtime> WebviewProtocol<'a, R> {
+
+    /// Allows the webview to access the file, and returns the URL to access it.  
+    /// If the file is already exposed, the same URL is returned.
+    /// 
+    /// The URL is valid until [`WebviewProtocol::unexpose`] or [`WebviewProtocol::unexpose_all`] is called,  
+    /// or the app is terminated.
+    /// 
+    /// # Args
+    /// - ***uri*** :  
+    /// Target file URI.  
+    /// This needs to be **readable** while exposed.
+    /// 
+    /// # Support
+    /// All.
+    pub fn expose(&self, uri: &FileUri) -> crate::Result<String> {
+        on_android!({
+            let mut exposed = self.lock_exposed();
+
+            let token = match exposed.iter().find(|(_, u)| *u == uri) {
+                Some((token, _)) => token.clone(),
+                None => {
+                    let token = new_token(uri);
+                    exposed.insert(token.clone(), uri.clone());
+                    token
+                }
+            };
+
+            let scheme = match self.use_https_scheme() {
+                true => "https",
+                false => "http",
+            };
+
+            Ok(format!("{scheme}://{PROTOCOL_SCHEME}.localhost/{token}"))
+        })
+    }
+
+    /// Disallows the webview to access the file exposed by [`WebviewProtocol::expose`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn unexpose(&self, uri: &FileUri) -> crate::Result<()> {
+        on_android!({
+            self.lock_exposed().retain(|_, u| u != uri);
+            Ok(())
+        })
+    }
+
+    /// Disallows the webview to access all files exposed by [`WebviewProtocol::expose`].
+    /// 
+    /// # Support
+    /// All.
+    pub fn unexpose_all(&self) -> crate::Result<()> {
+        on_android!({
+            self.lock_exposed().clear();
+            Ok(())
+        })
+    }
+
+
+    #[cfg(target_os = "android")]
+    fn lock_exposed(&self) -> std::sync::MutexGuard<'_, std::collections::HashMap<String, FileUri>> {
+        self.0.webview_protocol_exposed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(target_os = "android")]
+    fn use_https_scheme(&self) -> bool {
+        use tauri::Manager as _;
+
+        self.0.app.config().app.windows.first().map(|w| w.use_https_scheme).unwrap_or(false)
+    }
+
+    /// Handles a request to the protocol.
+    #[cfg(target_os = "android")]
+    pub(crate) fn handle(&self, req: &tauri::http::Request<Vec<u8>>) -> tauri::http::Response<Vec<u8>> {
+        use tauri::http::{header, Response, StatusCode};
+
+        let error = |status: StatusCode| {
+            Response::builder()
+                .status(status)
+                .body(Vec::new())
+                .unwrap()
+        };
+
+        let token = req.uri().path().trim_matches('/');
+        let Some(uri) = self.lock_exposed().get(token).cloned() else {
+            return error(StatusCode::FORBIDDEN)
+        };
+
+        let result = (|| -> crate::Result<_> {
+            let mime_type = self.0.get_mime_type(&uri)?.unwrap_or_else(|| "application/octet-stream".into());
+            let mut file = self.0.open_file(&uri, FileAccessMode::Read)?;
+            let len = file.metadata()?.len();
+
+            let range = response_range(req.headers().get(header::RANGE).and_then(|v| v.to_str().ok()), len);
+
+            let res = Response::builder()
+                .header(header::CONTENT_TYPE, mime_type)
+                .header(header::ACCEPT_RANGES, "bytes")
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+            Ok(match range {
+                None => {
+                    let mut buf = Vec::with_capacity(len as usize);
+                    file.read_to_end(&mut buf)?;
+
+                    res.status(StatusCode::OK)
+                        .header(header::CONTENT_LENGTH, buf.len())
+                        .body(buf)
+                },
+                Some(None) => {
+                    res.status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+                        .body(Vec::new())
+                },
+                Some(Some((start, end))) => {
+                    let mut buf = vec![0; (end - start + 1) as usize];
+                    file.seek(std::io::SeekFrom::Start(start))?;
+                    file.read_exact(&mut buf)?;
+
+                    res.status(StatusCode::PARTIAL_CONTENT)
+                        .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+                        .header(header::CONTENT_LENGTH, buf.len())
+                        .body(buf)
+                },
+            })
+        })();
+
+        match result {
+            Ok(Ok(res)) => res,
+            _ => error(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+}
+
+
+pub(crate) const PROTOCOL_SCHEME: &str = "android-fs";
+
+/// The maximum length of the response for an open-ended range, such as `bytes=100-`.  
+/// Media elements request the rest by the following requests.
+const MAX_OPEN_RANGE_LEN: u64 = 1024 * 1024;
+
+/// The maximum length of any response.  
+/// Requested ranges longer than this are shortened, which is allowed for servers.
+const MAX_RESPONSE_LEN: u64 = 8 * 1024 * 1024;
+
+/// Returns the range to respond for the value of the Range header.  
+/// `None` means the entire contents, and `Some(None)` means unsatisfiable.  
+/// Even without the Range header, contents longer than [`MAX_RESPONSE_LEN`] are responded partially.
+fn response_range(range_header: Option<&str>, len: u64) -> Option<Option<(u64, u64)>> {
+    range_header
+        .map(|v| parse_range(v, len))
+        .or_else(|| (MAX_RESPONSE_LEN < len).then_some(Some((0, MAX_RESPONSE_LEN - 1))))
+}
+
+/// Parses the value of the Range header, and limits its length to [`MAX_RESPONSE_LEN`].  
+/// Returns `None` if unsatisfiable.  
+/// Multiple ranges are not supported, so only the first one is used.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    if len == 0 {
+        return None
+    }
+
+    let (start, end) = match (start.is_empty(), end.is_empty()) {
+        // bytes=-n : the last n bytes
+        (true, false) => {
+            let n = end.parse::<u64>().ok()?.min(len);
+            if n == 0 {
+                return None
+            }
+            (len - n, len - 1)
+        },
+        // bytes=n-
+        (false, true) => {
+            let start = start.parse::<u64>().ok()?;
+            (start, start.saturating_add(MAX_OPEN_RANGE_LEN - 1).min(len - 1))
+        },
+        // bytes=n-m
+        (false, false) => {
+            let start = start.parse::<u64>().ok()?;
+            let end = end.parse::<u64>().ok()?.min(len - 1);
+            (start, end)
+        },
+        (true, true) => return None,
+    };
+
+    let end = end.min(start.saturating_add(MAX_RESPONSE_LEN - 1));
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn new_token(uri: &FileUri) -> String {
+    use std::hash::{BuildHasher as _, Hasher as _};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    // RandomState is seeded randomly per process,
+    // so the token cannot be guessed from the URI.
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write(uri.uri.as_bytes());
+    hasher.write_usize(id);
+
+    format!("{:016x}{id:x}", hasher.finish())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn parses_closed_range() {
+        assert_eq!(parse_range("bytes=0-0", 1000), Some((0, 0)));
+        assert_eq!(parse_range("bytes=100-199", 1000), Some((100, 199)));
+        assert_eq!(parse_range(" bytes= 100 - 199 ", 1000), Some((100, 199)));
+    }
+
+    #[test]
+    fn parses_suffix_range() {
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-5000", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=-0", 1000), None);
+    }
+
+    #[test]
+    fn limits_open_ended_range() {
+        assert_eq!(parse_range("bytes=100-", 1000), Some((100, 999)));
+        assert_eq!(parse_range("bytes=100-", 10 * MIB), Some((100, 100 + MAX_OPEN_RANGE_LEN - 1)));
+        assert_eq!(parse_range("bytes=0-", 10 * MIB), Some((0, MAX_OPEN_RANGE_LEN - 1)));
+    }
+
+    #[test]
+    fn clamps_range_past_eof() {
+        assert_eq!(parse_range("bytes=500-2000", 1000), Some((500, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=1000-2000", 1000), None);
+    }
+
+    #[test]
+    fn rejects_empty_contents() {
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("bytes=0-0", 0), None);
+        assert_eq!(parse_range("bytes=-1", 0), None);
+    }
+
+    #[test]
+    fn uses_first_of_multiple_ranges() {
+        assert_eq!(parse_range("bytes=0-9, 20-29", 1000), Some((0, 9)));
+        assert_eq!(parse_range("bytes=-10,0-9", 1000), Some((990, 999)));
+    }
+
+    #[test]
+    fn rejects_invalid_range() {
+        assert_eq!(parse_range("items=0-9", 1000), None);
+        assert_eq!(parse_range("bytes=a-b", 1000), None);
+        assert_eq!(parse_range("bytes=9-0", 1000), None);
+        assert_eq!(parse_range("bytes=-", 1000), None);
+        assert_eq!(parse_range("bytes=10", 1000), None);
+    }
+
+    #[test]
+    fn caps_to_max_response_len() {
+        assert_eq!(parse_range("bytes=0-99999999", 100 * MIB), Some((0, MAX_RESPONSE_LEN - 1)));
+        assert_eq!(parse_range("bytes=-99999999", 100 * MIB), Some((100 * MIB - 99999999, 100 * MIB - 99999999 + MAX_RESPONSE_LEN - 1)));
+        assert_eq!(response_range(None, 100 * MIB), Some(Some((0, MAX_RESPONSE_LEN - 1))));
+        assert_eq!(response_range(None, MAX_RESPONSE_LEN), None);
+        assert_eq!(response_range(Some("bytes=1000-"), 1000), Some(None));
+    }
+}
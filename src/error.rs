@@ -17,6 +17,27 @@ pub enum Error {
   
     #[error("{0}")]
     PluginInvoke(String),
+
+    #[error("This device does not have an encoder for {0:?}.")]
+    UnsupportedImageFormat(crate::ImageFormat),
+
+    #[error("The limit of persisted URI permissions has been reached.")]
+    PersistedUriPermissionLimitReached,
+
+    #[error("{0:?} access to the URI has not been granted.")]
+    UriPermissionNotGranted(crate::PersistableAccessMode),
+
+    #[error("Not enough space: {required} bytes are required, but only {available} bytes are available.")]
+    InsufficientSpace {
+        required: u64,
+        available: u64,
+    },
+
+    #[error("The backup is invalid: {0}")]
+    InvalidBackup(String),
+
+    #[error("The backup is incompatible with this app: {0}")]
+    IncompatibleBackup(String),
 }
 
 #[cfg(target_os = "android")]
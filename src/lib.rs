@@ -8,10 +8,27 @@ mod api;
 
 pub use models::*;
 pub use error::{Error, Result};
-pub use api::{AndroidFs, PrivateStorage, PublicStorage};
+pub use api::{AndroidFs, STORAGE_VOLUME_CHANGED_EVENT, PrivateStorage, PublicStorage, ThumbnailCache, PermissionRegistry, IncomingIntents, INCOMING_INTENT_EVENT, TempFile, TempDir, AtomicWriteFile, CacheManager, Store, STORE_CHANGED_EVENT};
+
+#[cfg(feature = "protocol")]
+pub use api::WebviewProtocol;
+
+#[cfg(feature = "watch")]
+pub use api::{WatchHandle, FILE_CHANGE_EVENT};
+
+#[cfg(feature = "encryption")]
+pub use api::{EncryptedStorage, EncryptedReader, EncryptedWriter, KeyProvider, SoftwareKeyProvider};
+
+#[cfg(feature = "compression")]
+pub use api::{CompressedStorage, CompressedReader, CompressedWriter};
 
 
 pub(crate) const TMP_DIR_RELATIVE_PATH: &str = "pluginAndroidFs-tmpDir-33bd1538-4434-dc4e-7e2f-515405cccbf9";
+pub(crate) const THUMBNAIL_CACHE_DIR_RELATIVE_PATH: &str = "pluginAndroidFs-thumbnailCacheDir-33bd1538-4434-dc4e-7e2f-515405cccbf9";
+pub(crate) const PERMISSION_REGISTRY_FILE_RELATIVE_PATH: &str = "pluginAndroidFs-permissionRegistry-33bd1538-4434-dc4e-7e2f-515405cccbf9.json";
+pub(crate) const INCOMING_INTENTS_FILE_RELATIVE_PATH: &str = "pluginAndroidFs-incomingIntents-33bd1538-4434-dc4e-7e2f-515405cccbf9.json";
+pub(crate) const ENCRYPTION_KEY_FILE_RELATIVE_PATH: &str = "pluginAndroidFs-encryptionKey-33bd1538-4434-dc4e-7e2f-515405cccbf9.bin";
+pub(crate) const STORE_DIR_RELATIVE_PATH: &str = "pluginAndroidFs-storeDir-33bd1538-4434-dc4e-7e2f-515405cccbf9";
 
 /// Initializes the plugin.
 pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
@@ -21,15 +38,56 @@ pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
 
             let afs = AndroidFs::new(app.clone(), api)?;
 
-            // Cleanup temporary directory
-            let _ = afs
-                .private_storage()
-                .remove_dir_all(PrivateDir::Cache, Some(TMP_DIR_RELATIVE_PATH));
+            // Cleanup temporary files, except those of other running processes
+            if let Ok(tmp_root) = afs.private_storage().resolve_path_with(PrivateDir::Cache, TMP_DIR_RELATIVE_PATH) {
+                api::remove_stale_temp_files(&tmp_root);
+            }
 
             app.manage(afs);
+
+            // Start receiving intents shared by other apps
+            #[cfg(target_os = "android")] {
+                let _ = app.android_fs().incoming_intents().listen();
+            }
+
+            // Start receiving changes of storage volumes, such as SD card being mounted
+            #[cfg(target_os = "android")] {
+                let _ = app.android_fs().listen_storage_volumes();
+            }
+
+            // Revalidate registered permissions in the background
+            #[cfg(target_os = "android")] {
+                let app = app.clone();
+                std::thread::spawn(move || {
+                    let _ = app.android_fs().permission_registry().validate();
+                });
+            }
+
             Ok(())
         });
 
+    #[cfg(feature = "protocol")]
+    let builder = builder.register_asynchronous_uri_scheme_protocol(api::PROTOCOL_SCHEME, |ctx, req, responder| {
+        #[cfg(target_os = "android")] {
+            let app = ctx.app_handle().clone();
+
+            // Reading a file may take a long time, so do not block the main thread.
+            std::thread::spawn(move || {
+                let res = app.android_fs().webview_protocol().handle(&req);
+                responder.respond(res);
+            });
+        }
+
+        #[cfg(not(target_os = "android"))] {
+            responder.respond(
+                tauri::http::Response::builder()
+                    .status(tauri::http::StatusCode::NOT_FOUND)
+                    .body(Vec::new())
+                    .unwrap()
+            );
+        }
+    });
+
     // https://github.com/aiueo13/tauri-plugin-android-fs/issues/1
     #[cfg(all(feature = "avoid-issue1", target_os = "android"))]
     let builder = {
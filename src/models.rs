@@ -98,6 +98,36 @@ pub enum Entry {
     }
 }
 
+/// Limits of the cache files managed by [`CacheManager`](crate::CacheManager).  
+/// When either is exceeded, the least recently used files are removed.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CacheLimit {
+
+    /// The maximum total bytes of the cache files.  
+    /// Default is None, which means no limit.
+    pub max_bytes: Option<u64>,
+
+    /// The maximum time since the cache file was last used.  
+    /// Default is None, which means no limit.
+    pub max_age: Option<std::time::Duration>,
+}
+
+/// Result of [`CacheManager::trim`](crate::CacheManager::trim).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CacheTrimResult {
+
+    /// The number of the removed files.
+    pub removed_files: usize,
+
+    /// The total bytes of the removed files.
+    pub removed_bytes: u64,
+
+    /// The total bytes of the remaining files.
+    pub remaining_bytes: u64,
+}
+
 /// Access mode
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub enum PersistableAccessMode {
@@ -158,6 +188,117 @@ impl PersistedUriPermission {
     }
 }
 
+/// Persisted URI permission registered in [`PermissionRegistry`](crate::PermissionRegistry).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RegisteredUriPermission {
+
+    /// App-defined key, such as `"backup-dir"`.
+    pub key: String,
+
+    /// URI of the target file or directory.
+    pub uri: FileUri,
+
+    /// App-defined label, such as a display name for the user.
+    pub label: Option<String>,
+
+    /// Time when this was registered.
+    pub registered_at: std::time::SystemTime,
+
+    /// Time when this was last validated.  
+    /// `None` if not validated yet.
+    pub validated_at: Option<std::time::SystemTime>,
+
+    /// Status at [`RegisteredUriPermission::validated_at`].
+    pub status: RegisteredUriPermissionStatus,
+
+    /// Time when this was last used, marked by [`PermissionRegistry::touch`](crate::PermissionRegistry::touch).  
+    /// `None` if not used yet.
+    #[serde(default)]
+    pub last_used_at: Option<std::time::SystemTime>,
+
+    /// If true, this is never evicted by [`PersistedUriPermissionEvictionPolicy`].
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl RegisteredUriPermission {
+
+    /// Returns true if the status is [`RegisteredUriPermissionStatus::Revoked`] or [`RegisteredUriPermissionStatus::NotFound`].
+    pub fn is_invalid(&self) -> bool {
+        matches!(self.status, RegisteredUriPermissionStatus::Revoked | RegisteredUriPermissionStatus::NotFound)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum RegisteredUriPermissionStatus {
+
+    /// Not validated yet.
+    Unchecked,
+
+    /// The permission is persisted and the target exists.
+    Valid {
+        can_read: bool,
+        can_write: bool,
+    },
+
+    /// The permission is no longer persisted.  
+    /// This occurs when the permission is relinquished by other apps, user, or by moving/removing entries.
+    Revoked,
+
+    /// The permission is persisted, but the target no longer exists.
+    NotFound,
+}
+
+/// Usage of persisted URI permissions.
+/// 
+/// Android limits the number of persisted URI permissions per app.  
+/// Once the limit is reached, taking a new permission fails silently.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PersistedUriPermissionCapacity {
+
+    /// Maximum number of persisted URI permissions.  
+    /// This is 512 on Android 11 (API level 30) or higher, otherwise 128.
+    pub limit: usize,
+
+    /// Number of currently persisted URI permissions.
+    pub used: usize,
+}
+
+impl PersistedUriPermissionCapacity {
+
+    /// Number of URI permissions that can still be persisted.
+    pub fn remaining(&self) -> usize {
+        self.limit.saturating_sub(self.used)
+    }
+}
+
+/// Policy for relinquishing persisted URI permissions when the limit is reached.
+/// 
+/// Permissions of entries pinned in [`PermissionRegistry`](crate::PermissionRegistry) are never relinquished.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum PersistedUriPermissionEvictionPolicy {
+
+    /// Never relinquish.
+    #[default]
+    None,
+
+    /// Relinquish the permission that was persisted the earliest.
+    OldestFirst,
+
+    /// Relinquish the permission that was used the least recently.  
+    /// For entries in [`PermissionRegistry`](crate::PermissionRegistry),  
+    /// the time marked by [`PermissionRegistry::touch`](crate::PermissionRegistry::touch) is used.  
+    /// Otherwise, the time when it was persisted is used.
+    LeastRecentlyUsed,
+}
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Size {
     pub width: u32,
@@ -201,6 +342,675 @@ pub enum ImageFormat {
         /// 0.0 means compress for the smallest size.  
         /// 1.0 means compress for max visual quality.  
         quality: f32
+    },
+
+    /// - Loss less
+    /// - Support transparency
+    WebpLossless,
+
+    /// - Lossy
+    /// - Unsupport transparency
+    /// - Depends on the hardware encoder of the device.  
+    /// Availability on a given device can be verified by calling [`AndroidFs::is_image_format_available`](crate::AndroidFs::is_image_format_available).
+    Heic,
+
+    /// - Lossy
+    /// - Unsupport transparency
+    /// - Depends on the hardware encoder of the device.  
+    /// Availability on a given device can be verified by calling [`AndroidFs::is_image_format_available`](crate::AndroidFs::is_image_format_available).
+    HeicWith {
+
+        /// Range is `0.0 ~ 1.0`  
+        /// 0.0 means compress for the smallest size.  
+        /// 1.0 means compress for max visual quality.
+        quality: f32
+    },
+
+    /// - Lossy
+    /// - Unsupport transparency
+    /// - Depends on the hardware encoder of the device.  
+    /// Availability on a given device can be verified by calling [`AndroidFs::is_image_format_available`](crate::AndroidFs::is_image_format_available).
+    /// - Requires the app to add `androidx.heifwriter:heifwriter:1.1.0-alpha02` or later to its Android dependencies.
+    Avif,
+
+    /// - Lossy
+    /// - Unsupport transparency
+    /// - Depends on the hardware encoder of the device.  
+    /// Availability on a given device can be verified by calling [`AndroidFs::is_image_format_available`](crate::AndroidFs::is_image_format_available).
+    /// - Requires the app to add `androidx.heifwriter:heifwriter:1.1.0-alpha02` or later to its Android dependencies.
+    AvifWith {
+
+        /// Range is `0.0 ~ 1.0`  
+        /// 0.0 means compress for the smallest size.  
+        /// 1.0 means compress for max visual quality.
+        quality: f32
+    },
+}
+
+impl ImageFormat {
+
+    /// Returns the format name and quality (`0 ~ 100`) passed to Kotlin side.
+    pub(crate) fn to_plugin_args(&self) -> (&'static str, u8) {
+        let (quality, format) = match self {
+            ImageFormat::Png => (1.0, "Png"),
+            ImageFormat::Jpeg => (0.75, "Jpeg"),
+            ImageFormat::Webp => (0.7, "Webp"),
+            ImageFormat::JpegWith { quality } => (*quality, "Jpeg"),
+            ImageFormat::WebpWith { quality } => (*quality, "Webp"),
+            ImageFormat::WebpLossless => (1.0, "WebpLossless"),
+            ImageFormat::Heic => (0.8, "Heic"),
+            ImageFormat::HeicWith { quality } => (*quality, "Heic"),
+            ImageFormat::Avif => (0.7, "Avif"),
+            ImageFormat::AvifWith { quality } => (*quality, "Avif"),
+        };
+        let quality = (quality * 100.0).clamp(0.0, 100.0) as u8;
+
+        (format, quality)
+    }
+}
+
+/// How the source image is fitted into [`TranscodeOptions::exact_size`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum ImageFit {
+
+    /// Scales the image to fit within the size while maintaining the aspect ratio.  
+    /// So one side of the result may be smaller than the specified size.
+    Contain,
+
+    /// Scales the image to cover the size while maintaining the aspect ratio,  
+    /// and crops the overflowing part from the center.
+    Cover,
+
+    /// Stretches the image to the size without maintaining the aspect ratio.
+    Fill,
+}
+
+/// Options for [`AndroidFs::transcode_image`](crate::AndroidFs::transcode_image).
+/// 
+/// # Examples
+/// ```
+/// use tauri_plugin_android_fs::{ImageFormat, Size, TranscodeOptions};
+///
+/// let options = TranscodeOptions {
+///     max_size: Some(Size { width: 1920, height: 1920 }),
+///     format: ImageFormat::JpegWith { quality: 0.8 },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TranscodeOptions {
+
+    /// The maximum size of the result.  
+    /// If the source is larger than this, it is scaled down while maintaining the aspect ratio.  
+    /// It is never scaled up.
+    /// 
+    /// This is ignored if ***exact_size*** is specified.  
+    /// If both are `None`, the original size is kept.
+    pub max_size: Option<Size>,
+
+    /// The size of the result.  
+    /// The source is scaled up or down according to ***fit***.
+    pub exact_size: Option<Size>,
+
+    /// How the source is fitted into ***exact_size***.
+    pub fit: ImageFit,
+
+    /// Image format of the result.
+    pub format: ImageFormat,
+
+    /// Indicates whether the metadata of the source, such as EXIF, is discarded.  
+    /// If false, EXIF is copied as much as possible, but only when the result is [`ImageFormat::Jpeg`] or [`ImageFormat::JpegWith`].
+    pub strip_metadata: bool,
+
+    /// Indicates whether the rotation by EXIF orientation of the source is applied to the pixels.  
+    /// If false, the pixels are kept as stored.
+    pub respect_exif_orientation: bool,
+}
+
+impl Default for TranscodeOptions {
+
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            exact_size: None,
+            fit: ImageFit::Contain,
+            format: ImageFormat::Jpeg,
+            strip_metadata: true,
+            respect_exif_orientation: true,
+        }
+    }
+}
+
+/// Options for the file pickers, such as [`AndroidFs::show_open_file_dialog_with`](crate::AndroidFs::show_open_file_dialog_with).
+/// 
+/// # Examples
+/// ```
+/// use tauri_plugin_android_fs::PickerOptions;
+///
+/// let options = PickerOptions {
+///     max_items: 10,
+///     mime_types: vec!["application/pdf".into()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PickerOptions {
+
+    /// The maximum number of files to be selected.  
+    /// If this is greater than 1, multiple file selection is allowed.  
+    /// If this is 0, it is treated as 1.
+    /// 
+    /// Some pickers can not limit the number of selection,  
+    /// in which case the excess files are discarded from the result.
+    pub max_items: usize,
+
+    /// The MIME types of the file to be selected.  
+    /// However, there is no guarantee that the returned file will match the specified types.  
+    /// If left empty, all file types will be available (equivalent to `["*/*"]`).
+    pub mime_types: Vec<String>,
+
+    /// Indicates whether only files that are available on the device are allowed,  
+    /// excluding files that need to be downloaded from cloud storage.
+    pub local_only: bool,
+
+    /// Indicate the initial location of dialog.  
+    /// See [`AndroidFs::show_open_file_dialog`](crate::AndroidFs::show_open_file_dialog) for details.  
+    /// This is ignored by some pickers.
+    pub initial_location: Option<FileUri>,
+
+    /// Indicates whether the files are returned in the order selected by the user,  
+    /// and the picker shows the selection order.  
+    /// This is used only by the media picker on devices that support it.
+    pub ordered: bool,
+}
+
+impl Default for PickerOptions {
+
+    fn default() -> Self {
+        Self {
+            max_items: 1,
+            mime_types: Vec::new(),
+            local_only: false,
+            initial_location: None,
+            ordered: false,
+        }
+    }
+}
+
+/// File selected by the file pickers, such as [`AndroidFs::show_open_file_dialog_with`](crate::AndroidFs::show_open_file_dialog_with).
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PickedFile {
+    pub uri: FileUri,
+
+    /// Display name of the file.  
+    /// If the provider does not provide it, the last segment of the URI is used.
+    pub name: String,
+
+    /// `None` if the provider does not provide it.
+    pub mime_type: Option<String>,
+
+    /// Size of the file in bytes.  
+    /// `None` if the provider does not provide it.
+    pub len: Option<u64>,
+
+    /// `None` if the provider does not provide it.
+    pub last_modified: Option<std::time::SystemTime>,
+}
+
+/// Options for [`AndroidFs::show_share_dialog`](crate::AndroidFs::show_share_dialog).
+/// 
+/// # Examples
+/// ```no_run
+/// use tauri_plugin_android_fs::{FileUri, ShareOptions};
+///
+/// let pdf: FileUri = unimplemented!();
+/// let image: FileUri = unimplemented!();
+///
+/// let options = ShareOptions {
+///     uris: vec![pdf, image],
+///     subject: Some("Monthly report".into()),
+///     text: Some("Here is the report for this month.".into()),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ShareOptions {
+
+    /// Target file URIs to share.  
+    /// These need to be **readable**.  
+    /// URIs given from [`PrivateStorage`](crate::PrivateStorage) or [`AndroidFs::show_open_visual_media_dialog`](crate::AndroidFs::show_open_visual_media_dialog) ***cannot*** be used.
+    pub uris: Vec<FileUri>,
+
+    /// Text to share with the files, such as a message body.
+    pub text: Option<String>,
+
+    /// Subject of the message, such as an email subject.
+    pub subject: Option<String>,
+
+    /// Title of the dialog.  
+    /// If this is None, the system default title is used.
+    pub title: Option<String>,
+
+    /// MIME type of the shared content.  
+    /// If this is None, the common MIME type of the files is used, such as `image/*` for `image/png` and `image/jpeg`.
+    pub mime_override: Option<String>,
+}
+
+/// Options for [`AndroidFs::show_view_dialog`](crate::AndroidFs::show_view_dialog).
+/// 
+/// # Examples
+/// ```no_run
+/// use tauri_plugin_android_fs::ViewOptions;
+///
+/// let options = ViewOptions {
+///     chooser_title: Some("Open with".into()),
+///     grant_write: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ViewOptions {
+
+    /// Whether to always show the app chooser,  
+    /// even if the user has set a default app or there is only one app that can handle it.
+    /// 
+    /// By default, this is true, same as [`AndroidFs::show_view_file_dialog`](crate::AndroidFs::show_view_file_dialog).
+    pub always_show_chooser: bool,
+
+    /// Title of the app chooser.  
+    /// If this is None, the system default title is used.
+    pub chooser_title: Option<String>,
+
+    /// Package name of the app to open the file, such as `com.google.android.apps.docs`.  
+    /// If this is specified, only that app's activities are candidates.
+    /// 
+    /// The apps can be obtained by [`AndroidFs::get_view_apps`](crate::AndroidFs::get_view_apps).
+    pub target_package: Option<String>,
+
+    /// Whether to grant the write permission of the file to the opened app, in addition to read.  
+    /// The file needs to be **writable** if this is true.
+    /// 
+    /// By default, this is false.
+    pub grant_write: bool,
+}
+
+impl Default for ViewOptions {
+
+    fn default() -> Self {
+        Self {
+            always_show_chooser: true,
+            chooser_title: None,
+            target_package: None,
+            grant_write: false,
+        }
+    }
+}
+
+/// Activity of an app.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AppComponent {
+
+    /// Package name of the app, such as `com.google.android.apps.docs`.
+    pub package_name: String,
+
+    /// Class name of the activity.
+    pub activity_name: String,
+}
+
+/// App that can handle a request, obtained by [`AndroidFs::get_view_apps`](crate::AndroidFs::get_view_apps).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AppInfo {
+
+    /// Activity that handles the request.
+    pub component: AppComponent,
+
+    /// User-visible name of the app.
+    pub label: String,
+
+    /// Icon of the app encoded as PNG.  
+    /// `None` if not requested or not available.
+    pub icon: Option<Vec<u8>>,
+}
+
+/// Intent received from other apps by [`IncomingIntents`](crate::IncomingIntents).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct IncomingIntent {
+
+    /// Unique ID to consume this by [`IncomingIntents::consume`](crate::IncomingIntents::consume).
+    pub id: String,
+
+    pub action: IncomingIntentAction,
+
+    /// Received file URIs.  
+    /// These are **readonly** in most cases.
+    pub uris: Vec<FileUri>,
+
+    /// MIME type specified by the sender.
+    pub mime_type: Option<String>,
+
+    /// Received text, such as a message body or URL.
+    pub text: Option<String>,
+
+    /// Received subject.
+    pub subject: Option<String>,
+
+    /// Time when this was received.
+    pub received_at: std::time::SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum IncomingIntentAction {
+
+    /// `ACTION_SEND`
+    Send,
+
+    /// `ACTION_SEND_MULTIPLE`
+    SendMultiple,
+
+    /// `ACTION_VIEW`
+    View,
+}
+
+/// Change of a file or directory watched by [`AndroidFs::watch`](crate::AndroidFs::watch).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FileChange {
+
+    /// ID of the watch, same as [`WatchHandle::id`](crate::WatchHandle::id).
+    pub watch_id: u64,
+
+    /// URI of the changed file or directory.  
+    /// For content URIs, this is the URI notified by the provider,  
+    /// so it may be the watched URI itself rather than its descendant.
+    pub uri: FileUri,
+
+    pub kind: FileChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum FileChangeKind {
+
+    /// Created, or moved into the watched location.
+    Create,
+
+    /// Contents or metadata changed.  
+    /// For content URIs, this is also used if the provider does not tell the kind of change.
+    Modify,
+
+    /// Removed, or moved out of the watched location.
+    Remove,
+}
+
+/// Storage volume, such as internal shared storage, SD card and USB drive.  
+/// This is obtained by [`AndroidFs::list_storage_volumes`](crate::AndroidFs::list_storage_volumes).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct StorageVolume {
+
+    /// ID of the volume.  
+    /// This is `primary` for the primary volume, and the filesystem UUID such as `1234-ABCD` for others.
+    /// 
+    /// This can be used for [`InitialLocation::Volume`].
+    pub id: String,
+
+    /// User-visible description of the volume, such as `SanDisk SD card`.
+    pub description: String,
+
+    /// Whether the volume is removable, such as SD card and USB drive.
+    pub removable: bool,
+
+    /// Whether the volume is the primary shared storage.
+    pub primary: bool,
+
+    pub state: StorageVolumeState,
+
+    /// Total size of the volume in bytes.  
+    /// `None` if the volume is not mounted.
+    pub total_bytes: Option<u64>,
+
+    /// Available size of the volume in bytes.  
+    /// `None` if the volume is not mounted.
+    pub free_bytes: Option<u64>,
+}
+
+/// State of [`StorageVolume`].
+/// 
+/// # References
+/// <https://developer.android.com/reference/android/os/Environment#MEDIA_MOUNTED>
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum StorageVolumeState {
+
+    /// Mounted with read and write access.
+    Mounted,
+
+    /// Mounted with read-only access.
+    MountedReadOnly,
+
+    /// Present but not mounted.
+    Unmounted,
+
+    /// Being checked before mounting.
+    Checking,
+
+    /// Being unmounted before removal.
+    Ejecting,
+
+    /// Not present.
+    Removed,
+
+    /// Removed before being unmounted.
+    BadRemoval,
+
+    /// Present but cannot be mounted, such as corrupted.
+    Unmountable,
+
+    /// Other states.
+    Unknown,
+}
+
+/// Options for writing files, such as [`AndroidFs::write_with`](crate::AndroidFs::write_with).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WriteOptions {
+
+    /// Whether to check that there is enough space before writing.  
+    /// If there is not, [`Error::InsufficientSpace`](crate::Error::InsufficientSpace) is returned without writing anything.
+    /// 
+    /// The space freed by replacing the existing contents is not taken into account.  
+    /// By default, this is false.
+    pub ensure_space: bool,
+}
+
+/// Compression format of [`PrivateStorage::compressed`](crate::PrivateStorage::compressed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum Compression {
+
+    /// - Widely supported
+    /// - Level 6
+    Gzip,
+
+    /// - Faster and smaller than gzip in most cases
+    /// - Level 3
+    Zstd,
+
+    GzipWith {
+
+        /// Range is `0 ~ 9`  
+        /// 0 means no compression.  
+        /// 9 means compress for the smallest size.
+        level: u32
+    },
+
+    ZstdWith {
+
+        /// Range is `1 ~ 22`  
+        /// 1 means compress for the fastest speed.  
+        /// 22 means compress for the smallest size.
+        level: i32
+    },
+}
+
+/// Format of the archive of [`AndroidFs::zip_dir`](crate::AndroidFs::zip_dir) and [`AndroidFs::unzip`](crate::AndroidFs::unzip).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum ArchiveFormat {
+
+    /// - Compressed by deflate
+    /// - Widely supported
+    Zip,
+
+    /// - Not compressed
+    Tar,
+
+    /// - Tar compressed by gzip
+    TarGz,
+}
+
+/// Options for [`AndroidFs::zip_dir`](crate::AndroidFs::zip_dir) and [`AndroidFs::unzip`](crate::AndroidFs::unzip).
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ArchiveOptions {
+
+    /// Format of the archive.  
+    /// If this is None, [`ArchiveFormat::Zip`] is used for creating,  
+    /// and the format is detected from the contents for extracting.  
+    /// By default, this is None.
+    pub format: Option<ArchiveFormat>,
+}
+
+/// Progress of [`AndroidFs::zip_dir_with_progress`](crate::AndroidFs::zip_dir_with_progress) and [`AndroidFs::unzip_with_progress`](crate::AndroidFs::unzip_with_progress).
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ArchiveProgress {
+
+    /// The number of the entries that have been processed.
+    pub processed_entries: u64,
+
+    /// The number of all entries.  
+    /// This is None if it is unknown until the end, such as when extracting tar.
+    pub total_entries: Option<u64>,
+
+    /// The uncompressed bytes that have been processed.
+    pub processed_bytes: u64,
+
+    /// The uncompressed bytes of all entries.  
+    /// This is None if it is unknown until the end, such as when extracting tar.
+    pub total_bytes: Option<u64>,
+
+    /// The path of the entry being processed, relative to the archived directory.
+    pub current_path: String,
+}
+
+/// How [`AndroidFs::import_private_data`](crate::AndroidFs::import_private_data) handles files that already exist.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum ImportConflictPolicy {
+
+    /// Replaces the existing files with those in the backup.
+    Overwrite,
+
+    /// Keeps the existing files, and does not restore those in the backup.
+    Skip,
+
+    /// Fails without restoring anything if any file in the backup already exists.
+    Fail,
+
+    /// Replaces all existing files in the directory with those in the backup,  
+    /// so that the directory becomes the same as when the backup was created.
+    /// 
+    /// The files are restored into a temporary directory first,  
+    /// and the existing files are removed only after all of them are restored.  
+    /// So if an error occurs while restoring, the existing files are kept.
+    ReplaceAll,
+}
+
+/// Result of [`AndroidFs::import_private_data`](crate::AndroidFs::import_private_data).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PrivateDataImportResult {
+
+    /// The number of the restored files.
+    pub restored_files: usize,
+
+    /// The number of the files skipped by [`ImportConflictPolicy::Skip`].
+    pub skipped_files: usize,
+}
+
+/// Options for [`AndroidFs::show_open_dir_dialog_with`](crate::AndroidFs::show_open_dir_dialog_with).
+/// 
+/// # Examples
+/// ```
+/// use tauri_plugin_android_fs::DirDialogOptions;
+///
+/// let options = DirDialogOptions {
+///     read_only: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DirDialogOptions {
+
+    /// Indicate the initial location of dialog.  
+    /// See [`AndroidFs::show_manage_dir_dialog`](crate::AndroidFs::show_manage_dir_dialog) for details.
+    pub initial_location: Option<FileUri>,
+
+    /// Indicates whether only read access is used.  
+    /// If true, write access is neither reported nor persisted,  
+    /// and only [`PersistableAccessMode::Read`] is persisted.
+    pub read_only: bool,
+
+    /// Indicates whether the permission is persisted across app restarts  
+    /// by [`AndroidFs::take_persistable_uri_permission_with`](crate::AndroidFs::take_persistable_uri_permission_with).
+    pub persist: bool,
+}
+
+impl Default for DirDialogOptions {
+
+    fn default() -> Self {
+        Self {
+            initial_location: None,
+            read_only: false,
+            persist: true,
+        }
+    }
+}
+
+/// Size limits of [`ThumbnailCache`](crate::ThumbnailCache).  
+/// When either is exceeded, the least recently used entries are removed.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ThumbnailCacheLimit {
+
+    /// The maximum total bytes of the cached thumbnails.  
+    /// Default is 64 MiB.
+    pub max_bytes: u64,
+
+    /// The maximum number of the cached thumbnails.  
+    /// Default is 4096.
+    pub max_entries: usize,
+}
+
+impl Default for ThumbnailCacheLimit {
+
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024 * 1024,
+            max_entries: 4096,
+        }
     }
 }
 
@@ -262,6 +1072,23 @@ pub enum VisualMediaTarget {
 
     /// Allow only images and videos to be selected.  
     ImageAndVideo,
+
+    /// Allow only images or videos of the specified MIME type to be selected,  
+    /// such as `image/gif`.
+    SingleMimeType(String),
+}
+
+impl VisualMediaTarget {
+
+    /// Returns the target name and MIME type for the plugin.
+    pub(crate) fn to_plugin_args(&self) -> (&'static str, Option<&str>) {
+        match self {
+            VisualMediaTarget::ImageOnly => ("ImageOnly", None),
+            VisualMediaTarget::VideoOnly => ("VideoOnly", None),
+            VisualMediaTarget::ImageAndVideo => ("ImageAndVideo", None),
+            VisualMediaTarget::SingleMimeType(mime_type) => ("SingleMimeType", Some(mime_type)),
+        }
+    }
 }
 
 /// The application specific directory.  
@@ -478,11 +1305,60 @@ pub enum InitialLocation<'a> {
     DirInPublicDir {
         base_dir: PublicDir,
         relative_path: &'a str,
-    }
+    },
+
+    /// Directory in the storage volume, such as SD card and USB drive.  
+    /// If ***relative_path*** is empty, this is the top directory of the volume.
+    Volume {
+        /// [`StorageVolume::id`]
+        id: &'a str,
+        relative_path: &'a str,
+    },
 }
 
 impl<T: Into<PublicDir>> From<T> for InitialLocation<'_> {
     fn from(value: T) -> Self {
         InitialLocation::PublicDir(value.into())
     }
-}
\ No newline at end of file
+}
+
+/// Target of [`AndroidFs::available_space`](crate::AndroidFs::available_space).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpaceTarget<'a> {
+
+    PrivateDir(PrivateDir),
+
+    PublicDir(PublicDir),
+
+    /// File or directory URI.  
+    /// For content URIs, this needs to be **readable** file.
+    Uri(&'a FileUri),
+}
+
+impl From<PrivateDir> for SpaceTarget<'_> {
+    fn from(value: PrivateDir) -> Self {
+        SpaceTarget::PrivateDir(value)
+    }
+}
+
+impl<'a> From<&'a FileUri> for SpaceTarget<'a> {
+    fn from(value: &'a FileUri) -> Self {
+        SpaceTarget::Uri(value)
+    }
+}
+
+macro_rules! impl_into_space_target {
+    ($target: ty) => {
+        impl From<$target> for SpaceTarget<'_> {
+            fn from(value: $target) -> Self {
+                SpaceTarget::PublicDir(value.into())
+            }
+        }
+    };
+}
+impl_into_space_target!(PublicDir);
+impl_into_space_target!(PublicImageDir);
+impl_into_space_target!(PublicVideoDir);
+impl_into_space_target!(PublicAudioDir);
+impl_into_space_target!(PublicGeneralPurposeDir);